@@ -1,8 +1,11 @@
 use std::fs::File;
 use std::io::stdout;
+use std::io::BufReader;
 use std::io::Write;
 use std::path::PathBuf;
 use std::process::exit;
+use std::str::FromStr;
+use std::time::Duration;
 
 use anyhow::Result as AnyhowResult;
 use anyhow::{anyhow, bail};
@@ -18,8 +21,17 @@ use conjure_oxide::model_from_json;
 use conjure_oxide::rule_engine::{
     get_rule_priorities, get_rules_vec, resolve_rule_sets, rewrite_model,
 };
-use conjure_oxide::utils::conjure::{get_minion_solutions, minion_solutions_to_json};
+use conjure_oxide::solver::adaptors::rustsat::{
+    model_from_dimacs, parse_dimacs, PbEncoding, ProofFormat, SatBackend,
+};
+use conjure_oxide::solver::adaptors::{Minion, SAT};
+use conjure_oxide::solver::{SolveOptions, Solver};
+use conjure_oxide::utils::conjure::{
+    get_brute_force_solutions, get_minion_solutions, get_sat_solutions, minion_solutions_to_json,
+};
+use conjure_oxide::utils::differential::{solve_with_each_family, DifferentialOptions};
 use conjure_oxide::SolverFamily;
+use minion_rs::{PropagationLevel, SearchOptions};
 
 #[derive(Parser)]
 #[command(author, version, about, long_about = None)]
@@ -27,6 +39,13 @@ struct Cli {
     #[arg(value_name = "INPUT_ESSENCE", help = "The input Essence file")]
     input_file: PathBuf,
 
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Read the input file as a DIMACS CNF, and solve it with the SAT solver family"
+    )]
+    dimacs: bool,
+
     #[arg(
         long,
         value_name = "EXTRA_RULE_SETS",
@@ -60,6 +79,129 @@ struct Cli {
         help = "Save solutions to a JSON file (prints to stdin by default)"
     )]
     output: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "MINION_FILE",
+        help = "Save the rewritten model as a Minion input file to the given file-path (Minion solver family only)"
+    )]
+    output_minion: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "DIMACS_FILE",
+        help = "Save the clauses given to the SAT solver as a DIMACS file to the given file-path"
+    )]
+    output_dimacs: Option<PathBuf>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Check each solution against the model before rewriting, failing if any is invalid"
+    )]
+    validate_solutions: bool,
+
+    #[arg(
+        long,
+        value_name = "SOLVERS",
+        value_delimiter = ',',
+        help = "Instead of solving with one solver family, solve with each of the given families (such as Minion,SAT) and report any differences in the solutions found"
+    )]
+    compare_solvers: Vec<SolverFamily>,
+
+    #[arg(
+        long,
+        value_name = "SECONDS",
+        help = "Stop the solver after the given number of seconds, returning the solutions found so far"
+    )]
+    time_limit: Option<f64>,
+
+    #[arg(
+        long,
+        value_name = "NODES",
+        help = "Stop Minion's search after the given number of nodes"
+    )]
+    minion_node_limit: Option<u64>,
+
+    #[arg(long, value_name = "SEED", help = "Random seed for Minion")]
+    minion_random_seed: Option<i32>,
+
+    #[arg(
+        long,
+        default_value_t = false,
+        help = "Enable restarts in Minion's search"
+    )]
+    minion_restarts: bool,
+
+    #[arg(
+        long,
+        value_name = "LEVEL",
+        help = "Propagation level for Minion to use before search (GAC, SAC, SSAC, SACBounds or SSACBounds)"
+    )]
+    minion_preprocess: Option<PropagationLevel>,
+
+    #[arg(
+        long,
+        value_name = "BACKEND",
+        default_value_t = SatBackend::default(),
+        help = "SAT solver to use with the SAT solver family (minisat-core, minisat-simp, cadical or glucose)"
+    )]
+    sat_backend: SatBackend,
+
+    #[arg(
+        long,
+        value_name = "ENCODING",
+        default_value_t = PbEncoding::default(),
+        help = "Encoding to use for sums of booleans with the SAT solver family (totalizer, gte or dpw)"
+    )]
+    sat_pb_encoding: PbEncoding,
+
+    #[arg(
+        long,
+        value_name = "PROOF_FILE",
+        help = "Save a proof of unsatisfiability from the SAT solver to the given file-path (needs a backend that supports proofs, such as cadical)"
+    )]
+    sat_proof: Option<PathBuf>,
+
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        default_value_t = ProofFormat::default(),
+        help = "Format of the proof saved with --sat-proof (drat or lrat)"
+    )]
+    sat_proof_format: ProofFormat,
+
+    #[arg(
+        long,
+        short = 'n',
+        value_name = "N|all",
+        default_value = "all",
+        help = "Stop the solver after finding the given number of solutions"
+    )]
+    number_of_solutions: NumberOfSolutions,
+}
+
+/// The value of `--number-of-solutions`.
+#[derive(Debug, Clone, Copy)]
+enum NumberOfSolutions {
+    All,
+    N(u64),
+}
+
+impl FromStr for NumberOfSolutions {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        if s.eq_ignore_ascii_case("all") {
+            return Ok(NumberOfSolutions::All);
+        }
+
+        match s.parse::<u64>() {
+            Ok(0) => Err("the number of solutions must be at least 1".into()),
+            Ok(n) => Ok(NumberOfSolutions::N(n)),
+            Err(_) => Err(format!("expected a number or `all`, got `{}`", s)),
+        }
+    }
 }
 
 #[allow(clippy::unwrap_used)]
@@ -73,7 +215,10 @@ pub fn main() -> AnyhowResult<()> {
         return Ok(());
     }
 
-    let target_family = cli.solver.unwrap_or(SolverFamily::Minion);
+    let target_family = match cli.dimacs {
+        true => SolverFamily::SAT,
+        false => cli.solver.unwrap_or(SolverFamily::Minion),
+    };
     let extra_rule_sets: Vec<String> = cli.extra_rule_sets;
     let out_file: Option<File> = match &cli.output {
         None => None,
@@ -97,11 +242,26 @@ pub fn main() -> AnyhowResult<()> {
         .with_target_writer("file,jsonparser", new_writer(log_file))
         .init();
 
-    if target_family != SolverFamily::Minion {
-        log::error!("Only the Minion solver is currently supported!");
+    if target_family == SolverFamily::SAT && !cli.sat_backend.is_enabled() {
+        log::error!(
+            "The {} SAT backend is not enabled: build with the `sat-{}` feature",
+            cli.sat_backend,
+            cli.sat_backend.feature()
+        );
         exit(1);
     }
 
+    if cli.sat_proof.is_some() {
+        if target_family != SolverFamily::SAT {
+            log::error!("--sat-proof can only be used with the SAT solver family");
+            exit(1);
+        }
+        if !cli.sat_backend.supports_proofs() {
+            log::error!("The {} SAT backend cannot write proofs", cli.sat_backend);
+            exit(1);
+        }
+    }
+
     let rule_sets = match resolve_rule_sets(target_family, &extra_rule_sets) {
         Ok(rs) => rs,
         Err(e) => {
@@ -138,27 +298,6 @@ pub fn main() -> AnyhowResult<()> {
         "Given input_file could not be converted to a string"
     ))?;
 
-    /******************************************************/
-    /*        Parse essence to json using Conjure         */
-    /******************************************************/
-
-    conjure_executable()
-        .map_err(|e| anyhow!("Could not find correct conjure executable: {}", e))?;
-
-    let mut cmd = std::process::Command::new("conjure");
-    let output = cmd
-        .arg("pretty")
-        .arg("--output-format=astjson")
-        .arg(input_file)
-        .output()?;
-
-    let conjure_stderr = String::from_utf8(output.stderr)?;
-    if !conjure_stderr.is_empty() {
-        bail!(conjure_stderr);
-    }
-
-    let astjson = String::from_utf8(output.stdout)?;
-
     let context = Context::new_ptr(
         target_family,
         extra_rule_sets.clone(),
@@ -168,16 +307,142 @@ pub fn main() -> AnyhowResult<()> {
 
     context.write().unwrap().file_name = Some(cli.input_file.to_str().expect("").into());
 
-    let mut model = model_from_json(&astjson, context.clone())?;
+    // the model as parsed, which solutions are checked against with --validate-solutions.
+    let original_model;
+    let model = if cli.dimacs {
+        // DIMACS files are already in CNF, so are given to the SAT solver as they are.
+        let dimacs = parse_dimacs(BufReader::new(File::open(&cli.input_file)?))?;
+        let model = model_from_dimacs(&dimacs, context.clone());
+        log::info!(target: "file", "DIMACS model: {}", json!(model));
+        original_model = model.clone();
+        model
+    } else {
+        /******************************************************/
+        /*        Parse essence to json using Conjure         */
+        /******************************************************/
+
+        conjure_executable()
+            .map_err(|e| anyhow!("Could not find correct conjure executable: {}", e))?;
+
+        let mut cmd = std::process::Command::new("conjure");
+        let output = cmd
+            .arg("pretty")
+            .arg("--output-format=astjson")
+            .arg(input_file)
+            .output()?;
+
+        let conjure_stderr = String::from_utf8(output.stderr)?;
+        if !conjure_stderr.is_empty() {
+            bail!(conjure_stderr);
+        }
+
+        let astjson = String::from_utf8(output.stdout)?;
+
+        let mut model = model_from_json(&astjson, context.clone())?;
+
+        log::info!(target: "file", "Initial model: {}", json!(model));
+        original_model = model.clone();
 
-    log::info!(target: "file", "Initial model: {}", json!(model));
+        log::info!(target: "file", "Rewriting model...");
+        model = rewrite_model(&model, &rule_sets)?;
 
-    log::info!(target: "file", "Rewriting model...");
-    model = rewrite_model(&model, &rule_sets)?;
+        log::info!(target: "file", "Rewritten model: {}", json!(model));
+        model
+    };
+
+    let mut search_options = SearchOptions::default();
+    search_options.node_limit = cli.minion_node_limit;
+    search_options.random_seed = cli.minion_random_seed;
+    search_options.restarts = cli.minion_restarts;
+    search_options.preprocess = cli.minion_preprocess;
 
-    log::info!(target: "file", "Rewritten model: {}", json!(model));
+    if let Some(path) = &cli.output_minion {
+        if target_family != SolverFamily::Minion {
+            bail!("--output-minion can only be used with the Minion solver family");
+        }
+        let solver = Solver::new(Minion::with_search_options(search_options.clone()))
+            .load_model(model.clone())?;
+        solver.write_solver_input_file(&mut File::create(path)?)?;
+        println!("Minion input file saved to {:?}", path.canonicalize()?);
+        println!(
+            "Run it with the Minion search options given using: minion {} {:?}",
+            search_options.to_args().join(" "),
+            path
+        );
+    }
+
+    if let Some(path) = &cli.output_dimacs {
+        if target_family != SolverFamily::SAT {
+            bail!("--output-dimacs can only be used with the SAT solver family");
+        }
+        let solver = Solver::new(
+            SAT::default()
+                .with_backend(cli.sat_backend)
+                .with_pb_encoding(cli.sat_pb_encoding),
+        )
+        .load_model(model.clone())?;
+        solver.write_solver_input_file(&mut File::create(path)?)?;
+        println!("DIMACS file saved to {:?}", path.canonicalize()?);
+    }
 
-    let solutions = get_minion_solutions(model)?; // ToDo we need to properly set the solver adaptor here, not hard code minion
+    let time_limit = cli
+        .time_limit
+        .map(Duration::try_from_secs_f64)
+        .transpose()
+        .map_err(|e| anyhow!("Invalid time limit: {}", e))?;
+
+    let solve_options = SolveOptions {
+        max_solutions: match cli.number_of_solutions {
+            NumberOfSolutions::All => None,
+            NumberOfSolutions::N(n) => Some(n),
+        },
+        time_limit,
+    };
+
+    if !cli.compare_solvers.is_empty() {
+        let options = DifferentialOptions {
+            extra_rule_sets: extra_rule_sets.clone(),
+            sat_backend: cli.sat_backend,
+            sat_pb_encoding: cli.sat_pb_encoding,
+            time_limit,
+        };
+        let report = solve_with_each_family(&original_model, &cli.compare_solvers, &options);
+        log::info!(target: "file", "Differential report: {}", report.to_json());
+
+        println!("Differential report:");
+        println!("{}", to_string_pretty(&report.to_json())?);
+        if !report.is_consistent() {
+            bail!("The solver families do not agree");
+        }
+        return Ok(());
+    }
+
+    let validate_against = cli.validate_solutions.then_some(&original_model);
+    let solutions = match target_family {
+        SolverFamily::Minion => {
+            get_minion_solutions(model, search_options, solve_options, validate_against)?
+        }
+        SolverFamily::SAT => {
+            let mut sat = SAT::default()
+                .with_backend(cli.sat_backend)
+                .with_pb_encoding(cli.sat_pb_encoding);
+            if let Some(path) = &cli.sat_proof {
+                sat = sat.with_proof(path, cli.sat_proof_format);
+            }
+            get_sat_solutions(model, sat, solve_options, validate_against)?
+        }
+        SolverFamily::BruteForce => {
+            get_brute_force_solutions(model, solve_options, validate_against)?
+        }
+    };
+
+    if let Some(path) = &cli.sat_proof {
+        if solutions.is_empty() {
+            println!("Proof saved to {:?}", path.canonicalize()?);
+        } else {
+            println!("The model has solutions, so {:?} is not a proof", path);
+        }
+    }
     log::info!(target: "file", "Solutions: {}", minion_solutions_to_json(&solutions));
 
     let solutions_json = minion_solutions_to_json(&solutions);
@@ -13,13 +13,14 @@ use thiserror::Error as ThisError;
 use std::fs::File;
 
 use crate::model_from_json;
-use crate::solver::adaptors::Minion;
-use crate::solver::Solver;
+use crate::solver::adaptors::{BruteForce, Minion, SAT};
+use crate::solver::{SearchIncomplete, SearchStatus, SolveOptions, Solver, SolverAdaptor};
 use crate::utils::json::sort_json_object;
 use crate::Error as ParseErr;
 use crate::Model;
 
 use glob::glob;
+use minion_rs::SearchOptions;
 
 #[derive(Debug, ThisError)]
 pub enum EssenceParseError {
@@ -78,24 +79,91 @@ pub fn parse_essence_file(
     Ok(parsed_model)
 }
 
-pub fn get_minion_solutions(model: Model) -> Result<Vec<HashMap<Name, Constant>>, anyhow::Error> {
-    let solver = Solver::new(Minion::new());
+/// Solves the given model, which must have been rewritten for Minion.
+///
+/// If `original_model` is given, each solution is checked against it, failing on the first
+/// invalid solution.
+pub fn get_minion_solutions(
+    model: Model,
+    search_options: SearchOptions,
+    solve_options: SolveOptions,
+    original_model: Option<&Model>,
+) -> Result<Vec<HashMap<Name, Constant>>, anyhow::Error> {
+    let solver =
+        Solver::new(Minion::with_search_options(search_options)).with_solve_options(solve_options);
 
-    println!("Building Minion model...");
+    get_solutions(solver, model, "Minion", original_model)
+}
+
+/// Solves the given model, which must have been rewritten for SAT, using the given SAT adaptor.
+///
+/// If `original_model` is given, each solution is checked against it, failing on the first
+/// invalid solution.
+pub fn get_sat_solutions(
+    model: Model,
+    sat: SAT,
+    solve_options: SolveOptions,
+    original_model: Option<&Model>,
+) -> Result<Vec<HashMap<Name, Constant>>, anyhow::Error> {
+    let solver_name = sat.get_name().unwrap_or(String::from("SAT"));
+    let solver = Solver::new(sat).with_solve_options(solve_options);
+
+    get_solutions(solver, model, &solver_name, original_model)
+}
+
+/// Solves the given model by trying every assignment. The model does not need to be rewritten.
+///
+/// If `original_model` is given, each solution is checked against it, failing on the first
+/// invalid solution.
+pub fn get_brute_force_solutions(
+    model: Model,
+    solve_options: SolveOptions,
+    original_model: Option<&Model>,
+) -> Result<Vec<HashMap<Name, Constant>>, anyhow::Error> {
+    let solver = Solver::new(BruteForce::new()).with_solve_options(solve_options);
+
+    get_solutions(solver, model, "Brute force", original_model)
+}
+
+/// Finds all solutions of the given model using the given solver, saving its stats to the model's
+/// context.
+fn get_solutions<A: SolverAdaptor>(
+    solver: Solver<A>,
+    model: Model,
+    solver_name: &str,
+    original_model: Option<&Model>,
+) -> Result<Vec<HashMap<Name, Constant>>, anyhow::Error> {
+    let solver = match original_model {
+        Some(original_model) => solver.with_validation(original_model),
+        None => solver,
+    };
+
+    println!("Building {} model...", solver_name);
     let solver = solver.load_model(model)?;
 
-    println!("Running Minion...");
+    println!("Running {}...", solver_name);
 
     let all_solutions_ref = Arc::new(Mutex::<Vec<HashMap<Name, Constant>>>::new(vec![]));
     let all_solutions_ref_2 = all_solutions_ref.clone();
     #[allow(clippy::unwrap_used)]
-    let solver = solver
-        .solve(Box::new(move |sols| {
-            let mut all_solutions = (*all_solutions_ref_2).lock().unwrap();
-            (*all_solutions).push(sols);
-            true
-        }))
-        .unwrap();
+    let solver = solver.solve(Box::new(move |sols| {
+        let mut all_solutions = (*all_solutions_ref_2).lock().unwrap();
+        (*all_solutions).push(sols);
+        true
+    }))?;
+
+    match solver.status() {
+        SearchStatus::Incomplete(SearchIncomplete::Timeout) => {
+            println!(
+                "{} timed out, so not all solutions may have been found.",
+                solver_name
+            );
+        }
+        SearchStatus::Incomplete(SearchIncomplete::SolutionLimitReached) => {
+            println!("Stopped after reaching the solution limit.");
+        }
+        _ => {}
+    }
 
     solver.save_stats_to_context();
 
@@ -0,0 +1,256 @@
+//! Differential testing: solving the same model with each solver family, and comparing the
+//! solutions found.
+//!
+//! Each family rewrites the model with its own rules, so a bug in the rules for one family, or in
+//! its solver adaptor, shows up as a difference between its solutions and those of the other
+//! families. Solutions are compared on the variables declared in the model, as auxiliary
+//! variables and representations differ between families.
+
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use serde_json::{json, Value as JsonValue};
+
+use conjure_core::ast::{Constant, Domain, Name};
+
+use crate::get_rule_set_by_name;
+use crate::rule_engine::{resolve_rule_sets, rewrite_model};
+use crate::solver::adaptors::rustsat::{PbEncoding, SatBackend};
+use crate::solver::adaptors::{BruteForce, Minion, SAT};
+use crate::solver::{SearchStatus, SolveOptions, Solver, SolverAdaptor, SolverFamily};
+use crate::utils::conjure::minion_solutions_to_json;
+use crate::Model;
+
+/// A solution, restricted to the variables declared in the model.
+pub type Projection = BTreeMap<Name, Constant>;
+
+/// Options for [`solve_with_each_family`].
+#[derive(Debug, Clone, Default)]
+pub struct DifferentialOptions {
+    /// Extra rule sets to enable when rewriting the model.
+    ///
+    /// A rule set is only enabled for a family if neither it nor its dependencies are specific to
+    /// another family, so, for example, `SAT_Direct` can be given to choose the integer encoding
+    /// for SAT without being enabled for Minion.
+    pub extra_rule_sets: Vec<String>,
+
+    /// The SAT solver to use with [`SolverFamily::SAT`].
+    pub sat_backend: SatBackend,
+
+    /// The encoding for sums of booleans to use with [`SolverFamily::SAT`].
+    pub sat_pb_encoding: PbEncoding,
+
+    /// Stop each solver after this amount of time. A family that times out is reported as an
+    /// error, as its solutions cannot be compared.
+    pub time_limit: Option<Duration>,
+}
+
+/// The solutions found with one family that differ from those found with another.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SolutionSetDiff {
+    /// The family whose solutions are compared against.
+    pub reference: SolverFamily,
+
+    pub family: SolverFamily,
+
+    /// Solutions found with the reference family, but not with `family`.
+    pub missing: Vec<Projection>,
+
+    /// Solutions found with `family`, but not with the reference family.
+    pub unexpected: Vec<Projection>,
+}
+
+/// The result of [`solve_with_each_family`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DifferentialReport {
+    /// The solutions found with each family, in the order that they were given, or why the model
+    /// could not be solved with that family.
+    pub outcomes: Vec<(SolverFamily, Result<BTreeSet<Projection>, String>)>,
+
+    /// How the solutions of each family differ from those of the first family that solved the
+    /// model. Families with the same solutions have no entry.
+    pub diffs: Vec<SolutionSetDiff>,
+}
+
+impl DifferentialReport {
+    /// Whether every family solved the model, finding the same solutions.
+    pub fn is_consistent(&self) -> bool {
+        self.diffs.is_empty() && self.outcomes.iter().all(|(_, x)| x.is_ok())
+    }
+
+    /// The report as JSON, giving the number of solutions found with each family, and the
+    /// differences between them.
+    pub fn to_json(&self) -> JsonValue {
+        let families: serde_json::Map<String, JsonValue> = self
+            .outcomes
+            .iter()
+            .map(|(family, outcome)| {
+                let value = match outcome {
+                    Ok(solutions) => json!({ "solutions": solutions.len() }),
+                    Err(e) => json!({ "error": e }),
+                };
+                (family.to_string(), value)
+            })
+            .collect();
+
+        let diffs: Vec<JsonValue> = self
+            .diffs
+            .iter()
+            .map(|diff| {
+                json!({
+                    "reference": diff.reference.to_string(),
+                    "family": diff.family.to_string(),
+                    "missing": projections_to_json(&diff.missing),
+                    "unexpected": projections_to_json(&diff.unexpected),
+                })
+            })
+            .collect();
+
+        json!({ "families": families, "diffs": diffs })
+    }
+}
+
+/// Rewrites the given model, as parsed, for each of the given families, then solves each version
+/// for all of its solutions and compares them.
+///
+/// Solutions are projected onto the variables declared in the model, with
+/// [`Name::UserName`]s, so that they can be compared. As Minion gives booleans as 0 and 1, values
+/// of boolean variables are given as booleans.
+pub fn solve_with_each_family(
+    model: &Model,
+    families: &[SolverFamily],
+    options: &DifferentialOptions,
+) -> DifferentialReport {
+    let outcomes: Vec<(SolverFamily, Result<BTreeSet<Projection>, String>)> = families
+        .iter()
+        .map(|&family| {
+            let outcome = solve_with_family(model, family, options)
+                .map(|solutions| solutions.iter().map(|x| project(model, x)).collect())
+                .map_err(|e| e.to_string());
+            (family, outcome)
+        })
+        .collect();
+
+    let mut diffs = Vec::new();
+    let mut solved = outcomes
+        .iter()
+        .filter_map(|(family, outcome)| outcome.as_ref().ok().map(|x| (*family, x)));
+    if let Some((reference, expected)) = solved.next() {
+        for (family, solutions) in solved {
+            let missing: Vec<Projection> = expected.difference(solutions).cloned().collect();
+            let unexpected: Vec<Projection> = solutions.difference(expected).cloned().collect();
+            if !missing.is_empty() || !unexpected.is_empty() {
+                diffs.push(SolutionSetDiff {
+                    reference,
+                    family,
+                    missing,
+                    unexpected,
+                });
+            }
+        }
+    }
+
+    DifferentialReport { outcomes, diffs }
+}
+
+/// Rewrites and solves the model with the given family, returning all of its solutions.
+fn solve_with_family(
+    model: &Model,
+    family: SolverFamily,
+    options: &DifferentialOptions,
+) -> Result<Vec<HashMap<Name, Constant>>, anyhow::Error> {
+    let extra_rule_sets = extra_rule_sets_for_family(family, &options.extra_rule_sets);
+    let rule_sets = resolve_rule_sets(family, &extra_rule_sets)?;
+    let model = rewrite_model(model, &rule_sets)?;
+
+    let solve_options = SolveOptions {
+        max_solutions: None,
+        time_limit: options.time_limit,
+    };
+    match family {
+        SolverFamily::Minion => find_all_solutions(
+            Solver::new(Minion::new()).with_solve_options(solve_options),
+            model,
+        ),
+        SolverFamily::SAT => find_all_solutions(
+            Solver::new(
+                SAT::default()
+                    .with_backend(options.sat_backend)
+                    .with_pb_encoding(options.sat_pb_encoding),
+            )
+            .with_solve_options(solve_options),
+            model,
+        ),
+        SolverFamily::BruteForce => find_all_solutions(
+            Solver::new(BruteForce::new()).with_solve_options(solve_options),
+            model,
+        ),
+    }
+}
+
+/// Gets the given rule sets that can be enabled for the given family: those that, along with
+/// their dependencies, are not specific to another family.
+///
+/// Unknown rule sets are kept, so that they are reported by [`resolve_rule_sets`].
+pub fn extra_rule_sets_for_family(family: SolverFamily, extra_rule_sets: &[String]) -> Vec<String> {
+    extra_rule_sets
+        .iter()
+        .filter(|name| match get_rule_set_by_name(name) {
+            Some(rule_set) => rule_set
+                .with_dependencies()
+                .iter()
+                .all(|x| x.solver_families.is_empty() || x.solver_families.contains(&family)),
+            None => true,
+        })
+        .cloned()
+        .collect()
+}
+
+/// Finds all solutions of the model, failing if search does not complete.
+fn find_all_solutions<A: SolverAdaptor>(
+    solver: Solver<A>,
+    model: Model,
+) -> Result<Vec<HashMap<Name, Constant>>, anyhow::Error> {
+    let solutions = Arc::new(Mutex::new(Vec::new()));
+    let solutions_2 = solutions.clone();
+    #[allow(clippy::unwrap_used)]
+    let solver = solver.load_model(model)?.solve(Box::new(move |solution| {
+        solutions_2.lock().unwrap().push(solution);
+        true
+    }))?;
+
+    if let SearchStatus::Incomplete(reason) = solver.status() {
+        anyhow::bail!("search did not complete: {:?}", reason);
+    }
+
+    #[allow(clippy::unwrap_used)]
+    let solutions = solutions.lock().unwrap().clone();
+    Ok(solutions)
+}
+
+/// Restricts the solution to the variables declared in the model, giving boolean variables
+/// boolean values.
+fn project(model: &Model, solution: &HashMap<Name, Constant>) -> Projection {
+    solution
+        .iter()
+        .filter(|(name, _)| matches!(name, Name::UserName(_)))
+        .filter_map(|(name, value)| {
+            let var = model.variables.get(name)?;
+            let value = match (&var.domain, value) {
+                (Domain::BoolDomain, Constant::Int(0)) => Constant::Bool(false),
+                (Domain::BoolDomain, Constant::Int(1)) => Constant::Bool(true),
+                _ => value.clone(),
+            };
+            Some((name.clone(), value))
+        })
+        .collect()
+}
+
+fn projections_to_json(projections: &[Projection]) -> JsonValue {
+    let solutions: Vec<HashMap<Name, Constant>> = projections
+        .iter()
+        .map(|x| x.clone().into_iter().collect())
+        .collect();
+    minion_solutions_to_json(&solutions)
+}
@@ -1,4 +1,5 @@
 pub mod conjure;
+pub mod differential;
 pub mod json;
 pub mod misc;
 pub mod testing;
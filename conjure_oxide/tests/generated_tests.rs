@@ -11,15 +11,20 @@ use conjure_core::ast::{Constant, Expression, Name};
 use conjure_core::context::Context;
 use conjure_oxide::rule_engine::resolve_rule_sets;
 use conjure_oxide::rule_engine::rewrite_model;
+use conjure_oxide::solver::SolveOptions;
 use conjure_oxide::utils::conjure::minion_solutions_to_json;
 use conjure_oxide::utils::conjure::{
     get_minion_solutions, get_solutions_from_conjure, parse_essence_file,
 };
+use conjure_oxide::utils::differential::{
+    extra_rule_sets_for_family, solve_with_each_family, DifferentialOptions,
+};
 use conjure_oxide::utils::testing::save_stats_json;
 use conjure_oxide::utils::testing::{
     read_minion_solutions_json, read_model_json, save_minion_solutions_json, save_model_json,
 };
 use conjure_oxide::SolverFamily;
+use minion_rs::SearchOptions;
 
 use uniplate::Uniplate;
 
@@ -27,9 +32,42 @@ use serde::Deserialize;
 
 use pretty_assertions::assert_eq;
 
-#[derive(Deserialize, Default)]
+#[derive(Deserialize)]
 struct TestConfig {
+    #[serde(default)]
     extra_rewriter_asserts: Vec<String>,
+
+    /// Extra rule sets to enable when rewriting the model. Each stage only enables those that are
+    /// not specific to another solver family, so, for example, `SAT_Direct` only applies to SAT.
+    #[serde(default = "default_extra_rule_sets")]
+    extra_rule_sets: Vec<String>,
+
+    /// Whether to check each solution against the model as parsed, before rewriting.
+    #[serde(default = "default_validate_solutions")]
+    validate_solutions: bool,
+
+    /// Solver families to solve the parsed model with, checking that they find the same solutions.
+    #[serde(default)]
+    compare_solver_families: Vec<SolverFamily>,
+}
+
+impl Default for TestConfig {
+    fn default() -> Self {
+        TestConfig {
+            extra_rewriter_asserts: vec![],
+            extra_rule_sets: default_extra_rule_sets(),
+            validate_solutions: default_validate_solutions(),
+            compare_solver_families: vec![],
+        }
+    }
+}
+
+fn default_validate_solutions() -> bool {
+    true
+}
+
+fn default_extra_rule_sets() -> Vec<String> {
+    vec!["Constant".to_string(), "Bubble".to_string()]
 }
 
 fn main() {
@@ -71,6 +109,10 @@ fn integration_test(path: &str, essence_base: &str, extension: &str) -> Result<(
 /// - **Parsing Stage**: Reads the Essence model file and verifies that it parses correctly.
 /// - **Rewrite Stage**: Applies a set of rules to the parsed model and validates the result.
 /// - **Solution Stage**: Uses Minion to solve the model and compares solutions with expected results.
+///   Unless disabled in the test's `config.toml`, each solution is also checked against the parsed
+///   model, before rewriting.
+/// - **Differential Stage**: If the test's `config.toml` gives `compare_solver_families`, rewrites
+///   and solves the parsed model with each of these families, checking that they agree.
 ///
 /// # Arguments
 ///
@@ -121,11 +163,12 @@ fn integration_test_inner(
     }
 
     assert_eq!(model, expected_model);
+    let parsed_model = model.clone();
 
     // Stage 2: Rewrite the model using the rule engine and check that the result is as expected
     let rule_sets = resolve_rule_sets(
         SolverFamily::Minion,
-        &vec!["Constant".to_string(), "Bubble".to_string()],
+        &extra_rule_sets_for_family(SolverFamily::Minion, &config.extra_rule_sets),
     )?;
     let model = rewrite_model(&model, &rule_sets)?;
     if verbose {
@@ -134,7 +177,7 @@ fn integration_test_inner(
 
     save_model_json(&model, path, essence_base, "rewrite", accept)?;
 
-    for extra_assert in config.extra_rewriter_asserts {
+    for extra_assert in &config.extra_rewriter_asserts {
         match extra_assert.as_str() {
             "vector_operators_have_partially_evaluated" => {
                 assert_vector_operators_have_partially_evaluated(&model)
@@ -151,7 +194,12 @@ fn integration_test_inner(
     assert_eq!(model, expected_model);
 
     // Stage 3: Run the model through the Minion solver and check that the solutions are as expected
-    let solutions = get_minion_solutions(model)?;
+    let solutions = get_minion_solutions(
+        model,
+        SearchOptions::default(),
+        SolveOptions::default(),
+        config.validate_solutions.then_some(&parsed_model),
+    )?;
 
     let solutions_json = save_minion_solutions_json(&solutions, path, essence_base, accept)?;
     if verbose {
@@ -171,7 +219,8 @@ fn integration_test_inner(
         for solset in &mut username_solutions {
             for (k, v) in solset.clone().into_iter() {
                 match k {
-                    conjure_core::ast::Name::MachineName(_) => {
+                    conjure_core::ast::Name::MachineName(_)
+                    | conjure_core::ast::Name::RepresentedName(_, _, _) => {
                         solset.remove(&k);
                     }
                     conjure_core::ast::Name::UserName(_) => match v {
@@ -226,6 +275,26 @@ fn integration_test_inner(
 
     save_stats_json(context, path, essence_base)?;
 
+    // Stage 4: Solve the parsed model with each solver family to compare, and check that they find
+    // the same solutions
+    if !config.compare_solver_families.is_empty() {
+        let options = DifferentialOptions {
+            extra_rule_sets: config.extra_rule_sets.clone(),
+            ..Default::default()
+        };
+        let report =
+            solve_with_each_family(&parsed_model, &config.compare_solver_families, &options);
+        if verbose {
+            println!("Differential report: {:#?}", report)
+        }
+
+        assert!(
+            report.is_consistent(),
+            "Solver families do not agree: {}",
+            serde_json::to_string_pretty(&report.to_json())?
+        );
+    }
+
     Ok(())
 }
 
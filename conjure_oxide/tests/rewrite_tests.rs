@@ -2,6 +2,8 @@ use core::panic;
 use std::collections::HashMap;
 use std::env;
 use std::process::exit;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
 
 use conjure_core::rules::eval_constant;
 use conjure_core::solver::SolverFamily;
@@ -9,7 +11,10 @@ use conjure_oxide::{
     ast::*,
     get_rule_by_name, get_rules,
     rule_engine::{resolve_rule_sets, rewrite_model},
-    solver::{adaptors, Solver},
+    solver::{
+        adaptors, SearchComplete, SearchIncomplete, SearchStatus, SolveOptions, Solver, SolverError,
+    },
+    utils::differential::{solve_with_each_family, DifferentialOptions},
     utils::testing::save_stats_json,
     Metadata, Model, Rule,
 };
@@ -322,6 +327,1119 @@ fn reduce_solve_xyz() {
     solver.solve(Box::new(|_| true)).unwrap();
 }
 
+#[test]
+fn minion_time_limit() {
+    // far too many solutions to enumerate in a second.
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Neq(
+            Metadata::new(),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("x0")),
+            )),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("x1")),
+            )),
+        ),
+        Default::default(),
+    );
+    for i in 0..20 {
+        model.variables.insert(
+            Name::UserName(format!("x{i}")),
+            DecisionVariable {
+                domain: Domain::IntDomain(vec![Range::Bounded(1, 9)]),
+            },
+        );
+    }
+
+    let solver: Solver<adaptors::Minion> =
+        Solver::new(adaptors::Minion::new()).with_time_limit(Duration::from_secs(1));
+    let solver = solver.load_model(model).unwrap();
+    let solver = solver.solve(Box::new(|_| true)).unwrap();
+
+    assert_eq!(
+        *solver.status(),
+        SearchStatus::Incomplete(SearchIncomplete::Timeout)
+    );
+}
+
+#[test]
+fn solution_limit() {
+    // x != y, for x, y in 1..3, has 6 solutions.
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Neq(
+            Metadata::new(),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("x")),
+            )),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("y")),
+            )),
+        ),
+        Default::default(),
+    );
+    for name in ["x", "y"] {
+        model.variables.insert(
+            Name::UserName(String::from(name)),
+            DecisionVariable {
+                domain: Domain::IntDomain(vec![Range::Bounded(1, 3)]),
+            },
+        );
+    }
+
+    let solve_options = SolveOptions {
+        max_solutions: Some(2),
+        ..Default::default()
+    };
+
+    let n_solutions = Arc::new(Mutex::new(0));
+    let n_solutions_2 = n_solutions.clone();
+
+    let solver: Solver<adaptors::Minion> =
+        Solver::new(adaptors::Minion::new()).with_solve_options(solve_options);
+    let solver = solver.load_model(model).unwrap();
+    let solver = solver
+        .solve(Box::new(move |_| {
+            *n_solutions_2.lock().unwrap() += 1;
+            true
+        }))
+        .unwrap();
+
+    assert_eq!(*n_solutions.lock().unwrap(), 2);
+    assert_eq!(
+        *solver.status(),
+        SearchStatus::Incomplete(SearchIncomplete::SolutionLimitReached)
+    );
+}
+
+#[test]
+fn minion_solver_stats() {
+    // x != y, for x, y in 1..3, has 6 solutions.
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Neq(
+            Metadata::new(),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("x")),
+            )),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("y")),
+            )),
+        ),
+        Default::default(),
+    );
+    for name in ["x", "y"] {
+        model.variables.insert(
+            Name::UserName(String::from(name)),
+            DecisionVariable {
+                domain: Domain::IntDomain(vec![Range::Bounded(1, 3)]),
+            },
+        );
+    }
+
+    let solver: Solver<adaptors::Minion> = Solver::new(adaptors::Minion::new());
+    let solver = solver.load_model(model).unwrap();
+    let solver = solver.solve(Box::new(|_| true)).unwrap();
+    let stats = solver.stats();
+
+    assert_eq!(stats.solutions_found, Some(6));
+    assert_eq!(stats.satisfiable, Some(true));
+    assert_eq!(stats.timed_out, Some(false));
+    assert!(stats.nodes.is_some());
+    assert!(stats.solver_solve_time.is_some());
+    assert!(stats.solver_setup_time.is_some());
+}
+
+#[test]
+fn minion_solve_mut_branch_and_bound() {
+    // maximise x, where x != y, for x, y in 1..5.
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Neq(
+            Metadata::new(),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("x")),
+            )),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("y")),
+            )),
+        ),
+        Default::default(),
+    );
+    for name in ["x", "y"] {
+        model.variables.insert(
+            Name::UserName(String::from(name)),
+            DecisionVariable {
+                domain: Domain::IntDomain(vec![Range::Bounded(1, 5)]),
+            },
+        );
+    }
+
+    let xs: Arc<Mutex<Vec<i32>>> = Arc::new(Mutex::new(vec![]));
+    let xs_2 = xs.clone();
+
+    let solver: Solver<adaptors::Minion> = Solver::new(adaptors::Minion::new());
+    let solver = solver.load_model(model).unwrap();
+    let solver = solver
+        .solve_mut(Box::new(move |solution, modifier| {
+            let Some(Constant::Int(x)) = solution.get(&Name::UserName(String::from("x"))) else {
+                panic!("x should be in the solution");
+            };
+            xs_2.lock().unwrap().push(*x);
+
+            // require a better solution: x < new_x, i.e. x <= new_x - 1.
+            modifier
+                .add_constraint(Expression::Ineq(
+                    Metadata::new(),
+                    Box::new(Expression::Constant(Metadata::new(), Constant::Int(*x))),
+                    Box::new(Expression::Reference(
+                        Metadata::new(),
+                        Name::UserName(String::from("x")),
+                    )),
+                    Box::new(Expression::Constant(Metadata::new(), Constant::Int(-1))),
+                ))
+                .is_ok()
+        }))
+        .unwrap();
+
+    let xs = xs.lock().unwrap();
+    assert_eq!(xs.last(), Some(&5));
+    assert!(xs.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(
+        *solver.status(),
+        SearchStatus::Complete(SearchComplete::HasSolutions)
+    );
+}
+
+#[test]
+fn minion_solve_mut_restart_does_not_repeat_solutions() {
+    // x != y, for x, y in 1..2, has 2 solutions.
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Neq(
+            Metadata::new(),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("x")),
+            )),
+            Box::new(Expression::Reference(
+                Metadata::new(),
+                Name::UserName(String::from("y")),
+            )),
+        ),
+        Default::default(),
+    );
+    for name in ["x", "y"] {
+        model.variables.insert(
+            Name::UserName(String::from(name)),
+            DecisionVariable {
+                domain: Domain::IntDomain(vec![Range::Bounded(1, 2)]),
+            },
+        );
+    }
+
+    let solutions: Arc<Mutex<Vec<HashMap<Name, Constant>>>> = Arc::new(Mutex::new(vec![]));
+    let solutions_2 = solutions.clone();
+
+    let solver: Solver<adaptors::Minion> = Solver::new(adaptors::Minion::new());
+    let solver = solver.load_model(model).unwrap();
+    solver
+        .solve_mut(Box::new(move |solution, modifier| {
+            let mut solutions = solutions_2.lock().unwrap();
+            solutions.push(solution);
+
+            // restart search after the first solution, which is then found again.
+            if solutions.len() == 1 {
+                assert!(modifier
+                    .add_constraint(Expression::Constant(Metadata::new(), Constant::Bool(true)))
+                    .is_ok());
+            }
+            true
+        }))
+        .unwrap();
+
+    let solutions = solutions.lock().unwrap();
+    assert_eq!(solutions.len(), 2);
+    assert_ne!(solutions[0], solutions[1]);
+}
+
+#[test]
+fn sat_solver_enumerates_solutions() {
+    // a \/ b, for booleans a and b, has 3 solutions.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::And(
+            Metadata::new(),
+            vec![Expression::Or(
+                Metadata::new(),
+                vec![
+                    Expression::Reference(Metadata::new(), a.clone()),
+                    Expression::Reference(Metadata::new(), b.clone()),
+                ],
+            )],
+        ),
+        Default::default(),
+    );
+    for name in [&a, &b] {
+        model.variables.insert(
+            name.clone(),
+            DecisionVariable {
+                domain: Domain::BoolDomain,
+            },
+        );
+    }
+
+    let solutions: Arc<Mutex<Vec<HashMap<Name, Constant>>>> = Arc::new(Mutex::new(vec![]));
+    let solutions_2 = solutions.clone();
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+    let solver = solver.load_model(model).unwrap();
+    let solver = solver
+        .solve(Box::new(move |solution| {
+            solutions_2.lock().unwrap().push(solution);
+            true
+        }))
+        .unwrap();
+
+    let solutions = solutions.lock().unwrap();
+    assert_eq!(solutions.len(), 3);
+    assert!(solutions
+        .iter()
+        .all(|x| x[&a] == Constant::Bool(true) || x[&b] == Constant::Bool(true)));
+    assert_eq!(
+        *solver.status(),
+        SearchStatus::Complete(SearchComplete::HasSolutions)
+    );
+    assert_eq!(solver.stats().solutions_found, Some(3));
+}
+
+#[test]
+fn sat_solutions_ignore_auxiliary_variables() {
+    // a \/ b, with an unconstrained auxiliary variable, still has 3 solutions.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+    let aux = Name::MachineName(0);
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Or(
+            Metadata::new(),
+            vec![
+                Expression::Reference(Metadata::new(), a.clone()),
+                Expression::Reference(Metadata::new(), b.clone()),
+            ],
+        ),
+        Default::default(),
+    );
+    for name in [&a, &b, &aux] {
+        model.variables.insert(
+            name.clone(),
+            DecisionVariable {
+                domain: Domain::BoolDomain,
+            },
+        );
+    }
+
+    let solutions: Arc<Mutex<Vec<HashMap<Name, Constant>>>> = Arc::new(Mutex::new(vec![]));
+    let solutions_2 = solutions.clone();
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+    let solver = solver.load_model(model).unwrap();
+    solver
+        .solve(Box::new(move |solution| {
+            solutions_2.lock().unwrap().push(solution);
+            true
+        }))
+        .unwrap();
+
+    let solutions = solutions.lock().unwrap();
+    assert_eq!(solutions.len(), 3);
+    assert!(solutions.iter().all(|x| !x.contains_key(&aux)));
+}
+
+#[test]
+fn sat_backends_agree() {
+    // a \/ b, for booleans a and b, has 3 solutions with every enabled backend.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+
+    for backend in adaptors::rustsat::SatBackend::ALL
+        .into_iter()
+        .filter(|x| x.is_enabled())
+    {
+        let mut model = Model::new(
+            HashMap::new(),
+            Expression::Or(
+                Metadata::new(),
+                vec![
+                    Expression::Reference(Metadata::new(), a.clone()),
+                    Expression::Reference(Metadata::new(), b.clone()),
+                ],
+            ),
+            Default::default(),
+        );
+        for name in [&a, &b] {
+            model.variables.insert(
+                name.clone(),
+                DecisionVariable {
+                    domain: Domain::BoolDomain,
+                },
+            );
+        }
+
+        let solver: Solver<adaptors::SAT> =
+            Solver::new(adaptors::SAT::default().with_backend(backend));
+        let solver = solver.load_model(model).unwrap();
+        let solver = solver.solve(Box::new(|_| true)).unwrap();
+
+        let stats = solver.stats();
+        assert_eq!(stats.solutions_found, Some(3), "{}", backend);
+        assert_eq!(stats.solver_adaptor, Some(format!("SAT ({})", backend)));
+    }
+}
+
+#[test]
+fn sat_solve_mut_minimise() {
+    // minimise the number of true variables, where a \/ b \/ c.
+    let names: Vec<Name> = ["a", "b", "c"]
+        .into_iter()
+        .map(|x| Name::UserName(String::from(x)))
+        .collect();
+    let refs: Vec<Expression> = names
+        .iter()
+        .map(|x| Expression::Reference(Metadata::new(), x.clone()))
+        .collect();
+
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Or(Metadata::new(), refs.clone()),
+        Default::default(),
+    );
+    for name in &names {
+        model.variables.insert(
+            name.clone(),
+            DecisionVariable {
+                domain: Domain::BoolDomain,
+            },
+        );
+    }
+
+    let counts: Arc<Mutex<Vec<i32>>> = Arc::new(Mutex::new(vec![]));
+    let counts_2 = counts.clone();
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+    let solver = solver.load_model(model).unwrap();
+    let solver = solver
+        .solve_mut(Box::new(move |solution, modifier| {
+            let count = solution
+                .values()
+                .filter(|x| **x == Constant::Bool(true))
+                .count() as i32;
+            counts_2.lock().unwrap().push(count);
+
+            // require a better solution.
+            modifier
+                .add_constraint(Expression::SumLeq(
+                    Metadata::new(),
+                    refs.clone(),
+                    Box::new(Expression::Constant(
+                        Metadata::new(),
+                        Constant::Int(count - 1),
+                    )),
+                ))
+                .is_ok()
+        }))
+        .unwrap();
+
+    let counts = counts.lock().unwrap();
+    assert_eq!(counts.last(), Some(&1));
+    assert!(counts.windows(2).all(|w| w[0] > w[1]));
+    assert_eq!(
+        *solver.status(),
+        SearchStatus::Complete(SearchComplete::HasSolutions)
+    );
+}
+
+#[test]
+fn sat_solve_mut_assumptions() {
+    // a \/ b, first assuming not a, then assuming a.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Or(
+            Metadata::new(),
+            vec![
+                Expression::Reference(Metadata::new(), a.clone()),
+                Expression::Reference(Metadata::new(), b.clone()),
+            ],
+        ),
+        Default::default(),
+    );
+    for name in [&a, &b] {
+        model.variables.insert(
+            name.clone(),
+            DecisionVariable {
+                domain: Domain::BoolDomain,
+            },
+        );
+    }
+
+    let solutions: Arc<Mutex<Vec<HashMap<Name, Constant>>>> = Arc::new(Mutex::new(vec![]));
+    let solutions_2 = solutions.clone();
+    let a_2 = a.clone();
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default().with_assumptions(
+        vec![Expression::Not(
+            Metadata::new(),
+            Box::new(Expression::Reference(Metadata::new(), a.clone())),
+        )],
+    ));
+    let solver = solver.load_model(model).unwrap();
+    solver
+        .solve_mut(Box::new(move |solution, modifier| {
+            let mut solutions = solutions_2.lock().unwrap();
+            solutions.push(solution);
+            if solutions.len() > 1 {
+                return true;
+            }
+
+            modifier
+                .set_assumptions(vec![Expression::Reference(Metadata::new(), a_2.clone())])
+                .is_ok()
+        }))
+        .unwrap();
+
+    let solutions = solutions.lock().unwrap();
+    assert_eq!(solutions.len(), 3);
+    assert_eq!(solutions[0][&a], Constant::Bool(false));
+    assert_eq!(solutions[0][&b], Constant::Bool(true));
+    assert!(solutions[1..].iter().all(|x| x[&a] == Constant::Bool(true)));
+    assert_ne!(solutions[1][&b], solutions[2][&b]);
+}
+
+#[test]
+fn sat_dimacs_round_trip() {
+    // a \/ !b, written as DIMACS and read back, still has 3 solutions over a and b.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Or(
+            Metadata::new(),
+            vec![
+                Expression::Reference(Metadata::new(), a.clone()),
+                Expression::Not(
+                    Metadata::new(),
+                    Box::new(Expression::Reference(Metadata::new(), b.clone())),
+                ),
+            ],
+        ),
+        Default::default(),
+    );
+    for name in [&a, &b] {
+        model.variables.insert(
+            name.clone(),
+            DecisionVariable {
+                domain: Domain::BoolDomain,
+            },
+        );
+    }
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+    let solver = solver.load_model(model).unwrap();
+    let mut out: Vec<u8> = Vec::new();
+    solver.write_solver_input_file(&mut out).unwrap();
+
+    let dimacs = adaptors::rustsat::parse_dimacs(out.as_slice()).unwrap();
+    assert_eq!(dimacs.n_vars, 2);
+    assert_eq!(dimacs.clauses.len(), 1);
+    let mut names: Vec<&String> = dimacs.var_names.values().collect();
+    names.sort();
+    assert_eq!(names, vec!["UserName(a)", "UserName(b)"]);
+
+    let model = adaptors::rustsat::model_from_dimacs(&dimacs, Default::default());
+    assert!(model.variables.contains_key(&a) && model.variables.contains_key(&b));
+
+    let solutions: Arc<Mutex<Vec<HashMap<Name, Constant>>>> = Arc::new(Mutex::new(vec![]));
+    let solutions_2 = solutions.clone();
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+    let solver = solver.load_model(model).unwrap();
+    solver
+        .solve(Box::new(move |solution| {
+            solutions_2.lock().unwrap().push(solution);
+            true
+        }))
+        .unwrap();
+
+    let solutions = solutions.lock().unwrap();
+    assert_eq!(solutions.len(), 3);
+    assert!(solutions
+        .iter()
+        .all(|x| x[&a] == Constant::Bool(true) || x[&b] == Constant::Bool(false)));
+}
+
+#[test]
+#[cfg_attr(
+    not(feature = "sat-cadical"),
+    ignore = "needs the cadical SAT backend (the sat-cadical feature)"
+)]
+fn sat_unsat_proof() {
+    // 3 pigeons do not fit in 2 holes.
+    let p = |i: i32, j: i32| Name::UserName(format!("p_{}_{}", i, j));
+    let reference = |name: Name| Expression::Reference(Metadata::new(), name);
+    let not = |name: Name| Expression::Not(Metadata::new(), Box::new(reference(name)));
+
+    let mut constraints = vec![];
+    for i in 0..3 {
+        constraints.push(Expression::Or(
+            Metadata::new(),
+            vec![reference(p(i, 0)), reference(p(i, 1))],
+        ));
+    }
+    for j in 0..2 {
+        for i in 0..3 {
+            for k in (i + 1)..3 {
+                constraints.push(Expression::Or(
+                    Metadata::new(),
+                    vec![not(p(i, j)), not(p(k, j))],
+                ));
+            }
+        }
+    }
+
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::And(Metadata::new(), constraints),
+        Default::default(),
+    );
+    for i in 0..3 {
+        for j in 0..2 {
+            model.variables.insert(
+                p(i, j),
+                DecisionVariable {
+                    domain: Domain::BoolDomain,
+                },
+            );
+        }
+    }
+
+    for format in [
+        adaptors::rustsat::ProofFormat::Drat,
+        adaptors::rustsat::ProofFormat::Lrat,
+    ] {
+        let proof_path =
+            env::temp_dir().join(format!("conjure_oxide_{}.{}", std::process::id(), format));
+
+        let solver: Solver<adaptors::SAT> = Solver::new(
+            adaptors::SAT::default()
+                .with_backend(adaptors::rustsat::SatBackend::Cadical)
+                .with_proof(&proof_path, format),
+        );
+        let solver = solver.load_model(model.clone()).unwrap();
+        let mut out: Vec<u8> = Vec::new();
+        solver.write_solver_input_file(&mut out).unwrap();
+        let dimacs = adaptors::rustsat::parse_dimacs(out.as_slice()).unwrap();
+
+        let solver = solver.solve(Box::new(|_| true)).unwrap();
+        assert_eq!(
+            *solver.status(),
+            SearchStatus::Complete(SearchComplete::NoSolutions)
+        );
+        assert_eq!(solver.stats().sat_proof_file, Some(proof_path.clone()));
+
+        let proof = std::io::BufReader::new(std::fs::File::open(&proof_path).unwrap());
+        let checked = match format {
+            adaptors::rustsat::ProofFormat::Drat => adaptors::rustsat::check_drat(&dimacs, proof),
+            adaptors::rustsat::ProofFormat::Lrat => adaptors::rustsat::check_lrat(&dimacs, proof),
+        };
+        std::fs::remove_file(&proof_path).unwrap();
+        assert!(checked.is_ok(), "{}: {:?}", format, checked);
+    }
+}
+
+#[test]
+fn solutions_are_validated_against_original_model() {
+    // a \/ b, wrongly rewritten to a \/ !b, so that a = b = false is found.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+    let reference = |name: &Name| Expression::Reference(Metadata::new(), name.clone());
+    let model_with = |constraint: Expression| {
+        let mut model = Model::new(HashMap::new(), constraint, Default::default());
+        for name in [&a, &b] {
+            model.variables.insert(
+                name.clone(),
+                DecisionVariable {
+                    domain: Domain::BoolDomain,
+                },
+            );
+        }
+        model
+    };
+
+    let original = model_with(Expression::Or(
+        Metadata::new(),
+        vec![reference(&a), reference(&b)],
+    ));
+    let rewritten = model_with(Expression::Or(
+        Metadata::new(),
+        vec![
+            reference(&a),
+            Expression::Not(Metadata::new(), Box::new(reference(&b))),
+        ],
+    ));
+
+    let solver: Solver<adaptors::SAT> =
+        Solver::new(adaptors::SAT::default()).with_validation(&original);
+    let result = solver
+        .load_model(original.clone())
+        .unwrap()
+        .solve(Box::new(|_| true));
+    assert!(result.is_ok());
+
+    let solver: Solver<adaptors::SAT> =
+        Solver::new(adaptors::SAT::default()).with_validation(&original);
+    let result = solver
+        .load_model(rewritten)
+        .unwrap()
+        .solve(Box::new(|_| true));
+    let Err(SolverError::InvalidSolution(message)) = result else {
+        panic!("expected an invalid solution");
+    };
+    assert!(message.contains(&original.constraints.to_string()));
+}
+
+#[test]
+fn brute_force_solves_unrewritten_model() {
+    // x / y = 1, for x in 1..3 and y in 0..3, is false when y = 0.
+    let x = Name::UserName(String::from("x"));
+    let y = Name::UserName(String::from("y"));
+    let reference = |name: &Name| Expression::Reference(Metadata::new(), name.clone());
+
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Eq(
+            Metadata::new(),
+            Box::new(Expression::UnsafeDiv(
+                Metadata::new(),
+                Box::new(reference(&x)),
+                Box::new(reference(&y)),
+            )),
+            Box::new(Expression::Constant(Metadata::new(), Constant::Int(1))),
+        ),
+        Default::default(),
+    );
+    model.variables.insert(
+        x.clone(),
+        DecisionVariable {
+            domain: Domain::IntDomain(vec![Range::Bounded(1, 3)]),
+        },
+    );
+    model.variables.insert(
+        y.clone(),
+        DecisionVariable {
+            domain: Domain::IntDomain(vec![Range::Bounded(0, 3)]),
+        },
+    );
+
+    let solutions = Arc::new(Mutex::new(Vec::new()));
+    let solutions_2 = solutions.clone();
+    let solver: Solver<adaptors::BruteForce> = Solver::new(adaptors::BruteForce::new());
+    let solver = solver
+        .load_model(model)
+        .unwrap()
+        .solve(Box::new(move |solution| {
+            solutions_2
+                .lock()
+                .unwrap()
+                .push((solution[&x].clone(), solution[&y].clone()));
+            true
+        }))
+        .unwrap();
+
+    assert_eq!(
+        solver.status(),
+        &SearchStatus::Complete(SearchComplete::HasSolutions)
+    );
+    assert_eq!(solver.stats().nodes, Some(12));
+
+    let mut solutions = solutions.lock().unwrap().clone();
+    solutions.sort_by_key(|(x, y)| (x.to_string(), y.to_string()));
+    let expected: Vec<(Constant, Constant)> = [(1, 1), (2, 2), (3, 2), (3, 3)]
+        .into_iter()
+        .map(|(x, y)| (Constant::Int(x), Constant::Int(y)))
+        .collect();
+    assert_eq!(solutions, expected);
+}
+
+#[test]
+fn solver_families_agree_on_disjunction() {
+    // a \/ b has 3 solutions.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+    let reference = |name: &Name| Expression::Reference(Metadata::new(), name.clone());
+
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Or(Metadata::new(), vec![reference(&a), reference(&b)]),
+        Default::default(),
+    );
+    for name in [&a, &b] {
+        model.variables.insert(
+            name.clone(),
+            DecisionVariable {
+                domain: Domain::BoolDomain,
+            },
+        );
+    }
+
+    let families = [
+        SolverFamily::Minion,
+        SolverFamily::SAT,
+        SolverFamily::BruteForce,
+    ];
+    let options = DifferentialOptions {
+        extra_rule_sets: vec!["Constant".to_string()],
+        ..Default::default()
+    };
+    let report = solve_with_each_family(&model, &families, &options);
+
+    assert!(report.is_consistent(), "{}", report.to_json());
+    for (_, outcome) in &report.outcomes {
+        let solutions = outcome.as_ref().unwrap();
+        assert_eq!(solutions.len(), 3);
+        assert!(solutions.iter().all(|x| x.len() == 2));
+    }
+}
+
+#[test]
+fn sat_tseitin_nested_formula() {
+    // (a /\ b) \/ (!a /\ c), for booleans a, b and c, has 4 solutions.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+    let c = Name::UserName(String::from("c"));
+    let reference = |name: &Name| Expression::Reference(Metadata::new(), name.clone());
+
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Or(
+            Metadata::new(),
+            vec![
+                Expression::And(Metadata::new(), vec![reference(&a), reference(&b)]),
+                Expression::And(
+                    Metadata::new(),
+                    vec![
+                        Expression::Not(Metadata::new(), Box::new(reference(&a))),
+                        reference(&c),
+                    ],
+                ),
+            ],
+        ),
+        Default::default(),
+    );
+    for name in [&a, &b, &c] {
+        model.variables.insert(
+            name.clone(),
+            DecisionVariable {
+                domain: Domain::BoolDomain,
+            },
+        );
+    }
+
+    let rule_sets = resolve_rule_sets(SolverFamily::SAT, &vec!["Constant".to_string()]).unwrap();
+    let model = rewrite_model(&model, &rule_sets).unwrap();
+
+    let solutions: Arc<Mutex<Vec<HashMap<Name, Constant>>>> = Arc::new(Mutex::new(vec![]));
+    let solutions_2 = solutions.clone();
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+    let solver = solver.load_model(model).unwrap();
+    solver
+        .solve(Box::new(move |solution| {
+            solutions_2.lock().unwrap().push(solution);
+            true
+        }))
+        .unwrap();
+
+    // the new variables are defined by the user's, so each solution should appear once.
+    let solutions = solutions.lock().unwrap();
+    let projected: Vec<Vec<Constant>> = solutions
+        .iter()
+        .map(|x| vec![x[&a].clone(), x[&b].clone(), x[&c].clone()])
+        .collect();
+    assert_eq!(projected.len(), 4);
+    for (i, x) in projected.iter().enumerate() {
+        assert!(!projected[..i].contains(x));
+    }
+
+    let t = Constant::Bool(true);
+    assert!(projected
+        .iter()
+        .all(|x| (x[0] == t && x[1] == t) || (x[0] != t && x[2] == t)));
+}
+
+#[test]
+fn sat_int_encodings() {
+    // x < y /\ x != 2, for x in 1..4 and y in {1, 2, 4}.
+    let x = Name::UserName(String::from("x"));
+    let y = Name::UserName(String::from("y"));
+
+    for rule_set in ["SAT_Direct", "SAT_Order", "SAT_Log"] {
+        let mut model = Model::new(
+            HashMap::new(),
+            Expression::And(
+                Metadata::new(),
+                vec![
+                    Expression::Lt(
+                        Metadata::new(),
+                        Box::new(Expression::Reference(Metadata::new(), x.clone())),
+                        Box::new(Expression::Reference(Metadata::new(), y.clone())),
+                    ),
+                    Expression::Neq(
+                        Metadata::new(),
+                        Box::new(Expression::Reference(Metadata::new(), x.clone())),
+                        Box::new(Expression::Constant(Metadata::new(), Constant::Int(2))),
+                    ),
+                ],
+            ),
+            Default::default(),
+        );
+        model.variables.insert(
+            x.clone(),
+            DecisionVariable {
+                domain: Domain::IntDomain(vec![Range::Bounded(1, 4)]),
+            },
+        );
+        model.variables.insert(
+            y.clone(),
+            DecisionVariable {
+                domain: Domain::IntDomain(vec![Range::Bounded(1, 2), Range::Single(4)]),
+            },
+        );
+
+        let rule_sets = resolve_rule_sets(
+            SolverFamily::SAT,
+            &vec!["Constant".to_string(), rule_set.to_string()],
+        )
+        .unwrap();
+        let model = rewrite_model(&model, &rule_sets).unwrap();
+
+        let solutions: Arc<Mutex<Vec<(Constant, Constant)>>> = Arc::new(Mutex::new(vec![]));
+        let solutions_2 = solutions.clone();
+        let (x_2, y_2) = (x.clone(), y.clone());
+
+        let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+        let solver = solver.load_model(model).unwrap();
+        solver
+            .solve(Box::new(move |solution| {
+                solutions_2
+                    .lock()
+                    .unwrap()
+                    .push((solution[&x_2].clone(), solution[&y_2].clone()));
+                true
+            }))
+            .unwrap();
+
+        let solutions = solutions.lock().unwrap();
+        assert_eq!(solutions.len(), 3, "{}", rule_set);
+        for (a, b) in [(1, 2), (1, 4), (3, 4)] {
+            assert!(
+                solutions.contains(&(Constant::Int(a), Constant::Int(b))),
+                "{}: ({}, {}) not found",
+                rule_set,
+                a,
+                b
+            );
+        }
+    }
+}
+
+#[test]
+fn sat_unused_int_var() {
+    // a, for a boolean a and x in {1, 2, 4}, which appears in no constraint.
+    let a = Name::UserName(String::from("a"));
+    let x = Name::UserName(String::from("x"));
+
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::Reference(Metadata::new(), a.clone()),
+        Default::default(),
+    );
+    model.variables.insert(
+        a.clone(),
+        DecisionVariable {
+            domain: Domain::BoolDomain,
+        },
+    );
+    model.variables.insert(
+        x.clone(),
+        DecisionVariable {
+            domain: Domain::IntDomain(vec![Range::Bounded(1, 2), Range::Single(4)]),
+        },
+    );
+
+    let rule_sets = resolve_rule_sets(
+        SolverFamily::SAT,
+        &vec!["Constant".to_string(), "SAT_Direct".to_string()],
+    )
+    .unwrap();
+    let model = rewrite_model(&model, &rule_sets).unwrap();
+
+    let solutions: Arc<Mutex<Vec<Constant>>> = Arc::new(Mutex::new(vec![]));
+    let solutions_2 = solutions.clone();
+    let x_2 = x.clone();
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+    let solver = solver.load_model(model).unwrap();
+    solver
+        .solve(Box::new(move |solution| {
+            solutions_2.lock().unwrap().push(solution[&x_2].clone());
+            true
+        }))
+        .unwrap();
+
+    let mut solutions = solutions.lock().unwrap().clone();
+    solutions.sort();
+    assert_eq!(
+        solutions,
+        vec![Constant::Int(1), Constant::Int(2), Constant::Int(4)]
+    );
+}
+
+#[test]
+fn sat_pb_encodings() {
+    // 2a + b + c = 2, given as a sum with a repeated term.
+    let a = Name::UserName(String::from("a"));
+    let b = Name::UserName(String::from("b"));
+    let c = Name::UserName(String::from("c"));
+
+    for pb_encoding in [
+        adaptors::rustsat::PbEncoding::Totalizer,
+        adaptors::rustsat::PbEncoding::Gte,
+        adaptors::rustsat::PbEncoding::Dpw,
+    ] {
+        let mut model = Model::new(
+            HashMap::new(),
+            Expression::Eq(
+                Metadata::new(),
+                Box::new(Expression::Sum(
+                    Metadata::new(),
+                    vec![
+                        Expression::Reference(Metadata::new(), a.clone()),
+                        Expression::Reference(Metadata::new(), a.clone()),
+                        Expression::Reference(Metadata::new(), b.clone()),
+                        Expression::Reference(Metadata::new(), c.clone()),
+                    ],
+                )),
+                Box::new(Expression::Constant(Metadata::new(), Constant::Int(2))),
+            ),
+            Default::default(),
+        );
+        for name in [&a, &b, &c] {
+            model.variables.insert(
+                name.clone(),
+                DecisionVariable {
+                    domain: Domain::BoolDomain,
+                },
+            );
+        }
+
+        let rule_sets =
+            resolve_rule_sets(SolverFamily::SAT, &vec!["Constant".to_string()]).unwrap();
+        let model = rewrite_model(&model, &rule_sets).unwrap();
+
+        let solutions: Arc<Mutex<Vec<HashMap<Name, Constant>>>> = Arc::new(Mutex::new(vec![]));
+        let solutions_2 = solutions.clone();
+
+        let solver: Solver<adaptors::SAT> =
+            Solver::new(adaptors::SAT::default().with_pb_encoding(pb_encoding));
+        let solver = solver.load_model(model).unwrap();
+        let solver = solver
+            .solve(Box::new(move |solution| {
+                solutions_2.lock().unwrap().push(solution);
+                true
+            }))
+            .unwrap();
+
+        // auxiliary variables of the encoding are not part of the solutions, so each solution is
+        // only found once.
+        let solutions = solutions.lock().unwrap();
+        assert_eq!(solutions.len(), 2, "{:?}", pb_encoding);
+        for (va, vb, vc) in [(true, false, false), (false, true, true)] {
+            assert!(
+                solutions.iter().any(|x| x[&a] == Constant::Bool(va)
+                    && x[&b] == Constant::Bool(vb)
+                    && x[&c] == Constant::Bool(vc)),
+                "{:?}: ({}, {}, {}) not found",
+                pb_encoding,
+                va,
+                vb,
+                vc
+            );
+        }
+
+        let stats = solver.stats();
+        assert!(
+            stats.sat_pb_clauses.is_some_and(|x| x > 0),
+            "{:?}",
+            pb_encoding
+        );
+        assert!(stats.sat_pb_aux_vars.is_some(), "{:?}", pb_encoding);
+    }
+}
+
+#[test]
+fn sat_rejects_more_than_one_int_encoding() {
+    // x#direct_1 /\ x#order_2, for x in 1..3, mixes the direct and order encodings of x.
+    let x = Name::UserName(String::from("x"));
+    let direct = Name::RepresentedName(Box::new(x.clone()), String::from("direct"), 1);
+    let order = Name::RepresentedName(Box::new(x.clone()), String::from("order"), 2);
+
+    let mut model = Model::new(
+        HashMap::new(),
+        Expression::And(
+            Metadata::new(),
+            vec![
+                Expression::Reference(Metadata::new(), direct.clone()),
+                Expression::Reference(Metadata::new(), order.clone()),
+            ],
+        ),
+        Default::default(),
+    );
+    model.variables.insert(
+        x.clone(),
+        DecisionVariable {
+            domain: Domain::IntDomain(vec![Range::Bounded(1, 3)]),
+        },
+    );
+    for name in [direct, order] {
+        model.variables.insert(
+            name,
+            DecisionVariable {
+                domain: Domain::BoolDomain,
+            },
+        );
+    }
+
+    let solver: Solver<adaptors::SAT> = Solver::new(adaptors::SAT::default());
+    let result = solver.load_model(model);
+    assert!(matches!(
+        result,
+        Err(SolverError::ModelFeatureNotSupported(_))
+    ));
+}
+
 #[test]
 fn rule_remove_double_negation() {
     let remove_double_negation = get_rule_by_name("remove_double_negation").unwrap();
@@ -652,6 +1770,34 @@ fn rule_distribute_or_over_and() {
     );
 }
 
+#[test]
+fn rule_tseitin_or_leaves_literal_conjunction() {
+    let tseitin_or = get_rule_by_name("tseitin_or").unwrap();
+    let reference = |i| Expression::Reference(Metadata::new(), Name::MachineName(i));
+    let model = Model::new_empty(Default::default());
+
+    // or(c, and(a, b)) is left to distribute_or_over_and, which needs no new variables.
+    let expr = Expression::Or(
+        Metadata::new(),
+        vec![
+            reference(3),
+            Expression::And(Metadata::new(), vec![reference(1), reference(2)]),
+        ],
+    );
+    assert!(tseitin_or.apply(&expr, &model).is_err());
+
+    // or(and(a, b), and(c, d)) would give an exponential number of clauses if distributed.
+    let expr = Expression::Or(
+        Metadata::new(),
+        vec![
+            Expression::And(Metadata::new(), vec![reference(1), reference(2)]),
+            Expression::And(Metadata::new(), vec![reference(3), reference(4)]),
+        ],
+    );
+    let red = tseitin_or.apply(&expr, &model).unwrap();
+    assert_eq!(red.symbols.len(), 2);
+}
+
 // #[test]
 // fn rule_ensure_div() {
 //     let ensure_div = get_rule_by_name("ensure_div").unwrap();
@@ -1,4 +1,6 @@
 use serde::{Deserialize, Serialize};
+
+use crate::ast::Constant;
 // use std::iter::Ste
 
 #[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
@@ -33,6 +35,23 @@ impl Domain {
         }
     }
 
+    /// Whether the given value is in the domain.
+    ///
+    /// As in Minion, booleans are 0 and 1 when compared with integers.
+    pub fn contains(&self, value: &Constant) -> bool {
+        let value = match value {
+            Constant::Int(i) => *i,
+            Constant::Bool(b) => *b as i32,
+        };
+        match self {
+            Domain::BoolDomain => value == 0 || value == 1,
+            Domain::IntDomain(ranges) => ranges.iter().any(|r| match r {
+                Range::Single(i) => *i == value,
+                Range::Bounded(i, j) => *i <= value && value <= *j,
+            }),
+        }
+    }
+
     /// Return an unoptimised domain that is the result of applying a binary i32 operation to two domains.
     ///
     /// The given operator may return None if the operation is not defined for its arguments.
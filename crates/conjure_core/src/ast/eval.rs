@@ -0,0 +1,369 @@
+use std::collections::{HashMap, HashSet};
+
+use thiserror::Error;
+
+use crate::ast::constants::Constant;
+use crate::ast::expressions::Expression;
+use crate::ast::symbol_table::Name;
+
+/// Errors returned by [`Expression::eval`].
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum EvalError {
+    #[error("variable {0} is not assigned a value")]
+    Unassigned(Name),
+
+    /// An integer expression has no value, for example because it divides by zero.
+    ///
+    /// This is only returned when the expression being evaluated is an integer expression: the
+    /// smallest boolean expression containing an undefined integer expression is false.
+    #[error("{0} is undefined")]
+    Undefined(Expression),
+
+    #[error("{0} overflows")]
+    Overflow(Expression),
+
+    #[error("expected {0} to be a boolean")]
+    NotBool(Expression),
+}
+
+impl Expression {
+    /// Evaluates the expression, given a value for each variable it references.
+    ///
+    /// Undefinedness follows the relational semantics of Essence: an integer expression is
+    /// undefined if any of its sub-expressions is, and the smallest boolean expression containing
+    /// an undefined integer expression is false. Division rounds down, and `DivEq` follows Minion's
+    /// `div_undefzero`, in which dividing by zero gives zero.
+    ///
+    /// As in Minion, booleans are 0 and 1 when used as integers, and the integers 0 and 1 can be
+    /// used as booleans.
+    pub fn eval(&self, assignment: &HashMap<Name, Constant>) -> Result<Constant, EvalError> {
+        match self {
+            Expression::Nothing => Ok(Constant::Bool(true)),
+            Expression::Constant(_, c) => Ok(c.clone()),
+            Expression::Reference(_, name) => assignment
+                .get(name)
+                .cloned()
+                .ok_or(EvalError::Unassigned(name.clone())),
+
+            Expression::Bubble(_, a, condition) => {
+                let condition = eval_bool(condition, assignment)?;
+                match a.eval(assignment) {
+                    Ok(Constant::Bool(a)) => Ok((a && condition).into()),
+                    Ok(_) if !condition => Err(EvalError::Undefined(self.clone())),
+                    res => res,
+                }
+            }
+
+            Expression::Sum(_, exprs) => {
+                let mut total: i32 = 0;
+                for e in exprs {
+                    total = total
+                        .checked_add(eval_int(e, assignment)?)
+                        .ok_or(EvalError::Overflow(self.clone()))?;
+                }
+                Ok(total.into())
+            }
+            Expression::Min(_, exprs) => eval_ints(exprs, assignment)?
+                .into_iter()
+                .min()
+                .map(Into::into)
+                .ok_or(EvalError::Undefined(self.clone())),
+            Expression::Max(_, exprs) => eval_ints(exprs, assignment)?
+                .into_iter()
+                .max()
+                .map(Into::into)
+                .ok_or(EvalError::Undefined(self.clone())),
+            Expression::SafeDiv(_, a, b) | Expression::UnsafeDiv(_, a, b) => {
+                match div(eval_int(a, assignment)?, eval_int(b, assignment)?) {
+                    Some(Some(x)) => Ok(x.into()),
+                    Some(None) => Err(EvalError::Overflow(self.clone())),
+                    None => Err(EvalError::Undefined(self.clone())),
+                }
+            }
+
+            Expression::Not(_, a) => Ok((!eval_bool(a, assignment)?).into()),
+            Expression::And(_, exprs) => {
+                let mut value = true;
+                for e in exprs {
+                    value &= eval_bool(e, assignment)?;
+                }
+                Ok(value.into())
+            }
+            Expression::Or(_, exprs) => {
+                let mut value = false;
+                for e in exprs {
+                    value |= eval_bool(e, assignment)?;
+                }
+                Ok(value.into())
+            }
+
+            Expression::Eq(_, a, b) => {
+                relation(|| Ok(eq(&a.eval(assignment)?, &b.eval(assignment)?)))
+            }
+            Expression::Neq(_, a, b) => {
+                relation(|| Ok(!eq(&a.eval(assignment)?, &b.eval(assignment)?)))
+            }
+            Expression::Geq(_, a, b) => {
+                relation(|| Ok(eval_int(a, assignment)? >= eval_int(b, assignment)?))
+            }
+            Expression::Leq(_, a, b) => {
+                relation(|| Ok(eval_int(a, assignment)? <= eval_int(b, assignment)?))
+            }
+            Expression::Gt(_, a, b) => {
+                relation(|| Ok(eval_int(a, assignment)? > eval_int(b, assignment)?))
+            }
+            Expression::Lt(_, a, b) => {
+                relation(|| Ok(eval_int(a, assignment)? < eval_int(b, assignment)?))
+            }
+            Expression::AllDiff(_, exprs) => relation(|| {
+                let values = eval_ints(exprs, assignment)?;
+                Ok(values.iter().collect::<HashSet<_>>().len() == values.len())
+            }),
+
+            // sums are compared as 64-bit integers, so that these never overflow.
+            Expression::SumEq(_, exprs, a) => {
+                relation(|| Ok(sum_i64(exprs, assignment)? == eval_int(a, assignment)? as i64))
+            }
+            Expression::SumGeq(_, exprs, a) => {
+                relation(|| Ok(sum_i64(exprs, assignment)? >= eval_int(a, assignment)? as i64))
+            }
+            Expression::SumLeq(_, exprs, a) => {
+                relation(|| Ok(sum_i64(exprs, assignment)? <= eval_int(a, assignment)? as i64))
+            }
+
+            // a <= b + c
+            Expression::Ineq(_, a, b, c) => relation(|| {
+                let (a, b, c) = (
+                    eval_int(a, assignment)?,
+                    eval_int(b, assignment)?,
+                    eval_int(c, assignment)?,
+                );
+                Ok(a as i64 <= b as i64 + c as i64)
+            }),
+
+            // a / b = c, where a / 0 = 0
+            Expression::DivEq(_, a, b, c) => relation(|| {
+                let (a, b, c) = (
+                    eval_int(a, assignment)?,
+                    eval_int(b, assignment)?,
+                    eval_int(c, assignment)?,
+                );
+                Ok(match div(a, b) {
+                    Some(x) => x == Some(c),
+                    None => c == 0,
+                })
+            }),
+
+            Expression::WatchedLiteral(_, name, k) => {
+                let value = assignment
+                    .get(name)
+                    .ok_or(EvalError::Unassigned(name.clone()))?;
+                Ok(eq(value, k).into())
+            }
+
+            // a <-> r
+            Expression::Reify(_, a, r) => {
+                Ok((eval_bool(a, assignment)? == eval_bool(r, assignment)?).into())
+            }
+        }
+    }
+}
+
+/// Evaluates an expression that should be an integer.
+fn eval_int(expr: &Expression, assignment: &HashMap<Name, Constant>) -> Result<i32, EvalError> {
+    match expr.eval(assignment)? {
+        Constant::Int(x) => Ok(x),
+        Constant::Bool(x) => Ok(x as i32),
+    }
+}
+
+fn eval_ints(
+    exprs: &[Expression],
+    assignment: &HashMap<Name, Constant>,
+) -> Result<Vec<i32>, EvalError> {
+    exprs.iter().map(|e| eval_int(e, assignment)).collect()
+}
+
+fn sum_i64(exprs: &[Expression], assignment: &HashMap<Name, Constant>) -> Result<i64, EvalError> {
+    Ok(eval_ints(exprs, assignment)?
+        .into_iter()
+        .map(|x| x as i64)
+        .sum())
+}
+
+/// Evaluates an expression that should be a boolean.
+fn eval_bool(expr: &Expression, assignment: &HashMap<Name, Constant>) -> Result<bool, EvalError> {
+    match expr.eval(assignment)? {
+        Constant::Bool(x) => Ok(x),
+        Constant::Int(0) => Ok(false),
+        Constant::Int(1) => Ok(true),
+        Constant::Int(_) => Err(EvalError::NotBool(expr.clone())),
+    }
+}
+
+/// Evaluates a boolean expression over integers, which is false if any of them is undefined.
+fn relation(f: impl FnOnce() -> Result<bool, EvalError>) -> Result<Constant, EvalError> {
+    match f() {
+        Err(EvalError::Undefined(_)) => Ok(Constant::Bool(false)),
+        res => res.map(Constant::Bool),
+    }
+}
+
+/// Whether two constants are equal, treating booleans as 0 and 1.
+fn eq(a: &Constant, b: &Constant) -> bool {
+    let as_int = |x: &Constant| match x {
+        Constant::Int(x) => *x,
+        Constant::Bool(x) => *x as i32,
+    };
+    as_int(a) == as_int(b)
+}
+
+/// Divides, rounding down.
+///
+/// Returns `None` when dividing by zero, and `Some(None)` on overflow.
+fn div(a: i32, b: i32) -> Option<Option<i32>> {
+    if b == 0 {
+        return None;
+    }
+    let Some(q) = a.checked_div(b) else {
+        return Some(None);
+    };
+    if a % b != 0 && ((a < 0) != (b < 0)) {
+        Some(Some(q - 1))
+    } else {
+        Some(Some(q))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::metadata::Metadata;
+
+    fn var(name: &str) -> Expression {
+        Expression::Reference(Metadata::new(), Name::UserName(name.to_owned()))
+    }
+
+    fn assignment(values: &[(&str, Constant)]) -> HashMap<Name, Constant> {
+        values
+            .iter()
+            .map(|(name, value)| (Name::UserName((*name).to_owned()), value.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn test_eval_arithmetic() {
+        let values = assignment(&[("x", Constant::Int(-7)), ("y", Constant::Int(2))]);
+        let sum = Expression::Sum(Metadata::new(), vec![var("x"), var("y"), 3.into()]);
+        assert_eq!(sum.eval(&values), Ok(Constant::Int(-2)));
+
+        // division rounds down
+        let div = Expression::UnsafeDiv(Metadata::new(), Box::new(var("x")), Box::new(var("y")));
+        assert_eq!(div.eval(&values), Ok(Constant::Int(-4)));
+
+        let min = Expression::Min(Metadata::new(), vec![var("x"), var("y")]);
+        assert_eq!(min.eval(&values), Ok(Constant::Int(-7)));
+
+        let unassigned = Expression::Sum(Metadata::new(), vec![var("z")]);
+        assert_eq!(
+            unassigned.eval(&values),
+            Err(EvalError::Unassigned(Name::UserName("z".to_owned())))
+        );
+    }
+
+    #[test]
+    fn test_eval_undefined() {
+        let values = assignment(&[("x", Constant::Int(1)), ("y", Constant::Int(0))]);
+        let div = Expression::UnsafeDiv(Metadata::new(), Box::new(var("x")), Box::new(var("y")));
+        assert_eq!(div.eval(&values), Err(EvalError::Undefined(div.clone())));
+
+        // the smallest boolean expression containing x / 0 is false.
+        let eq = Expression::Eq(Metadata::new(), Box::new(div.clone()), Box::new(0.into()));
+        assert_eq!(eq.eval(&values), Ok(Constant::Bool(false)));
+
+        let not = Expression::Not(Metadata::new(), Box::new(eq));
+        assert_eq!(not.eval(&values), Ok(Constant::Bool(true)));
+
+        // {x @ y != 0}
+        let bubble = Expression::Bubble(
+            Metadata::new(),
+            Box::new(var("x")),
+            Box::new(Expression::Neq(
+                Metadata::new(),
+                Box::new(var("y")),
+                Box::new(0.into()),
+            )),
+        );
+        assert_eq!(
+            bubble.eval(&values),
+            Err(EvalError::Undefined(bubble.clone()))
+        );
+    }
+
+    #[test]
+    fn test_eval_flattened_constraints() {
+        let values = assignment(&[
+            ("x", Constant::Int(7)),
+            ("y", Constant::Int(2)),
+            ("z", Constant::Int(3)),
+            ("b", Constant::Bool(true)),
+        ]);
+
+        // 7 + 2 <= 9
+        let sum_leq = Expression::SumLeq(
+            Metadata::new(),
+            vec![var("x"), var("y")],
+            Box::new(9.into()),
+        );
+        assert_eq!(sum_leq.eval(&values), Ok(Constant::Bool(true)));
+
+        // 7 <= 2 + 3
+        let ineq = Expression::Ineq(
+            Metadata::new(),
+            Box::new(var("x")),
+            Box::new(var("y")),
+            Box::new(3.into()),
+        );
+        assert_eq!(ineq.eval(&values), Ok(Constant::Bool(false)));
+
+        // 7 / 2 = 3
+        let div_eq = Expression::DivEq(
+            Metadata::new(),
+            Box::new(var("x")),
+            Box::new(var("y")),
+            Box::new(var("z")),
+        );
+        assert_eq!(div_eq.eval(&values), Ok(Constant::Bool(true)));
+
+        // 7 / 0 = 0
+        let div_eq_zero = Expression::DivEq(
+            Metadata::new(),
+            Box::new(var("x")),
+            Box::new(0.into()),
+            Box::new(0.into()),
+        );
+        assert_eq!(div_eq_zero.eval(&values), Ok(Constant::Bool(true)));
+
+        let literal = Expression::WatchedLiteral(
+            Metadata::new(),
+            Name::UserName("b".to_owned()),
+            Constant::Bool(false),
+        );
+        assert_eq!(literal.eval(&values), Ok(Constant::Bool(false)));
+
+        // (x = 7) <-> b
+        let reify = Expression::Reify(
+            Metadata::new(),
+            Box::new(Expression::Eq(
+                Metadata::new(),
+                Box::new(var("x")),
+                Box::new(7.into()),
+            )),
+            Box::new(var("b")),
+        );
+        assert_eq!(reify.eval(&values), Ok(Constant::Bool(true)));
+
+        let all_diff = Expression::AllDiff(Metadata::new(), vec![var("x"), var("y"), 2.into()]);
+        assert_eq!(all_diff.eval(&values), Ok(Constant::Bool(false)));
+    }
+}
@@ -306,6 +306,15 @@ impl Display for Expression {
             Expression::Reference(_, name) => match name {
                 Name::MachineName(n) => write!(f, "_{}", n),
                 Name::UserName(s) => write!(f, "{}", s),
+                Name::RepresentedName(name, repr, i) => {
+                    write!(
+                        f,
+                        "{}#{}_{}",
+                        Expression::Reference(Metadata::new(), *name.clone()),
+                        repr,
+                        i
+                    )
+                }
             },
             Expression::Nothing => write!(f, "Nothing"),
             Expression::Sum(_, expressions) => {
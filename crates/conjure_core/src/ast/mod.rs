@@ -1,5 +1,6 @@
 mod constants;
 mod domains;
+mod eval;
 mod expressions;
 mod symbol_table;
 pub mod types;
@@ -8,6 +9,7 @@ mod variables;
 pub use constants::Constant;
 pub use domains::Domain;
 pub use domains::Range;
+pub use eval::EvalError;
 pub use expressions::Expression;
 pub use symbol_table::Name;
 pub use symbol_table::SymbolTable;
@@ -9,6 +9,13 @@ use crate::ast::variables::DecisionVariable;
 pub enum Name {
     UserName(String),
     MachineName(i32),
+
+    /// A boolean variable used to represent another variable, for example when encoding integer
+    /// variables for SAT.
+    ///
+    /// Contains the represented variable, the name of the representation, and an index within the
+    /// representation.
+    RepresentedName(Box<Name>, String, i32),
 }
 
 impl Display for Name {
@@ -16,6 +23,9 @@ impl Display for Name {
         match self {
             Name::UserName(s) => write!(f, "UserName({})", s),
             Name::MachineName(i) => write!(f, "MachineName({})", i),
+            Name::RepresentedName(name, repr, i) => {
+                write!(f, "RepresentedName({}, {}, {})", name, repr, i)
+            }
         }
     }
 }
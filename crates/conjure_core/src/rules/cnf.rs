@@ -2,7 +2,148 @@
 /*        This file contains rules for converting logic expressions to CNF         */
 /***********************************************************************************/
 
-use conjure_core::rule_engine::register_rule_set;
+use conjure_core::ast::{
+    Constant as Const, DecisionVariable, Domain, Expression as Expr, SymbolTable,
+};
+use conjure_core::metadata::Metadata;
+use conjure_core::rule_engine::{
+    register_rule, register_rule_set, ApplicationError, ApplicationResult, Reduction,
+};
 use conjure_core::solver::SolverFamily;
+use conjure_core::Model;
 
 register_rule_set!("CNF", 100, ("Base"), (SolverFamily::SAT));
+
+/**
+ * Replace non-literal disjuncts with new boolean variables, using the Tseitin transformation:
+ * ```text
+ * or([a, and([b, c])]) ~> or([a, t]) ; t <-> and([b, c])
+ * ```
+ *
+ * The definition of each new variable is posted as clauses:
+ * ```text
+ * t <-> and([b, c]) ~> or([not t, b]), or([not t, c]), or([t, not b, not c])
+ * t <-> or([b, c])  ~> or([t, not b]), or([t, not c]), or([not t, b, c])
+ * ```
+ *
+ * Nested sub-formulas are given variables of their own, so the number of clauses is linear in the
+ * size of the expression, where `distribute_or_over_and` can give an exponential number of
+ * clauses.
+ *
+ * A single `and` of literals is left to `distribute_or_over_and`, which gives one clause per
+ * conjunct without any new variables:
+ * ```text
+ * or([a, and([b, c])]) ~> and([or([a, b]), or([a, c])])
+ * ```
+ */
+#[register_rule(("CNF", 8500))]
+fn tseitin_or(expr: &Expr, mdl: &Model) -> ApplicationResult {
+    let Expr::Or(metadata, exprs) = expr else {
+        return Err(ApplicationError::RuleNotApplicable);
+    };
+
+    let non_literals: Vec<&Expr> = exprs.iter().filter(|e| needs_variable(e)).collect();
+    match non_literals.as_slice() {
+        [] => return Err(ApplicationError::RuleNotApplicable),
+        [Expr::And(_, conjuncts)] if conjuncts.iter().all(is_literal) => {
+            return Err(ApplicationError::RuleNotApplicable);
+        }
+        _ => {}
+    }
+
+    let mut new_vars = SymbolTable::new();
+    let mut clauses = Vec::new();
+
+    let mut new_exprs = Vec::new();
+    for e in exprs {
+        if needs_variable(e) {
+            new_exprs.push(to_literal(e, mdl, &mut new_vars, &mut clauses)?);
+        } else {
+            new_exprs.push(e.clone());
+        }
+    }
+
+    Ok(Reduction::new(
+        Expr::Or(metadata.clone_dirty(), new_exprs),
+        Expr::And(Metadata::new(), clauses),
+        new_vars,
+    ))
+}
+
+/// Whether the given disjunct must be replaced by a new variable to get a clause.
+///
+/// Nested `or`s are not included, as these are flattened by `unwrap_nested_or`.
+fn needs_variable(expr: &Expr) -> bool {
+    match expr {
+        Expr::And(_, _) => true,
+        Expr::Not(_, e) => matches!(
+            e.as_ref(),
+            Expr::And(_, _) | Expr::Or(_, _) | Expr::Not(_, _)
+        ),
+        _ => false,
+    }
+}
+
+/// Whether the given expression is a boolean literal.
+fn is_literal(expr: &Expr) -> bool {
+    match expr {
+        Expr::Reference(_, _) | Expr::Constant(_, Const::Bool(_)) => true,
+        Expr::Not(_, e) => matches!(e.as_ref(), Expr::Reference(_, _)),
+        _ => false,
+    }
+}
+
+/// Gets a literal equivalent to the given boolean formula, adding new variables for its
+/// sub-formulas and clauses defining them.
+fn to_literal(
+    expr: &Expr,
+    mdl: &Model,
+    new_vars: &mut SymbolTable,
+    clauses: &mut Vec<Expr>,
+) -> Result<Expr, ApplicationError> {
+    match expr {
+        Expr::Reference(_, _) | Expr::Constant(_, Const::Bool(_)) => Ok(expr.clone()),
+        Expr::Not(_, e) => Ok(negate(&to_literal(e, mdl, new_vars, clauses)?)),
+        Expr::And(_, exprs) | Expr::Or(_, exprs) => {
+            let lits = exprs
+                .iter()
+                .map(|e| to_literal(e, mdl, new_vars, clauses))
+                .collect::<Result<Vec<_>, _>>()?;
+
+            let new_name = mdl.gensym();
+            new_vars.insert(new_name.clone(), DecisionVariable::new(Domain::BoolDomain));
+            let var = Expr::Reference(Metadata::new(), new_name);
+
+            // and(lits) = not(or(not lits)), so both are defined as t <-> or(lits), with t and
+            // the literals negated for and.
+            let (t, lits) = match expr {
+                Expr::And(_, _) => (negate(&var), lits.iter().map(negate).collect()),
+                _ => (var.clone(), lits),
+            };
+
+            // t -> or(lits)
+            let mut long_clause = vec![negate(&t)];
+            long_clause.extend(lits.iter().cloned());
+            clauses.push(Expr::Or(Metadata::new(), long_clause));
+
+            // or(lits) -> t
+            for lit in lits {
+                clauses.push(Expr::Or(Metadata::new(), vec![t.clone(), negate(&lit)]));
+            }
+
+            Ok(var)
+        }
+        _ => Err(ApplicationError::RuleNotApplicable),
+    }
+}
+
+/// Negates a literal.
+fn negate(lit: &Expr) -> Expr {
+    match lit {
+        Expr::Not(_, e) => *e.clone(),
+        Expr::Constant(metadata, Const::Bool(val)) => {
+            Expr::Constant(metadata.clone(), Const::Bool(!val))
+        }
+        _ => Expr::Not(Metadata::new(), Box::new(lit.clone())),
+    }
+}
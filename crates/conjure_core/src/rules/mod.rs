@@ -12,3 +12,5 @@ mod cnf;
 mod constant;
 mod minion;
 mod partial_eval;
+mod sat_int;
+mod sat_pb;
@@ -0,0 +1,390 @@
+/***********************************************************************************/
+/*        This file contains rules for encoding integer variables for SAT          */
+/***********************************************************************************/
+
+//! Each integer variable is represented by boolean variables named
+//! `RepresentedName(x, <encoding>, i)`, which the SAT adaptor decodes back into integers:
+//!
+//! - `direct`: `x#direct_v` is true iff `x = v`, for each value `v` in the domain of `x`.
+//! - `order`: `x#order_v` is true iff `x >= v`, for each value `v` in the domain of `x` except
+//!   the smallest.
+//! - `log`: `x#log_i` is bit `i` of `x - lb`, where `lb` is the smallest value in the domain of
+//!   `x`.
+//!
+//! The encoding is chosen by enabling exactly one of the `SAT_Direct`, `SAT_Order` and `SAT_Log`
+//! rule sets. Enabling more than one is not supported: their rules have the same priority, so each
+//! comparison would be encoded by whichever rule happens to be tried first, and the SAT adaptor
+//! rejects models with a variable represented in more than one encoding.
+
+use conjure_core::ast::{
+    Constant as Const, DecisionVariable, Domain, Expression as Expr, Name, SymbolTable,
+};
+use conjure_core::metadata::Metadata;
+use conjure_core::rule_engine::{
+    register_rule, register_rule_set, ApplicationError, ApplicationResult, Reduction,
+};
+use conjure_core::Model;
+
+register_rule_set!("SAT_Direct", 100, ("CNF"));
+register_rule_set!("SAT_Order", 100, ("CNF"));
+register_rule_set!("SAT_Log", 100, ("CNF"));
+
+/**
+ * Encode comparisons of integer variables using the direct encoding:
+ * ```text
+ * x = 2  ~> x#direct_2
+ * x <= 2 ~> or([x#direct_1, x#direct_2])      (x in 1..3)
+ * ```
+ */
+#[register_rule(("SAT_Direct", 9000))]
+fn int_to_direct(expr: &Expr, mdl: &Model) -> ApplicationResult {
+    encode_comparison(expr, mdl, IntEncoding::Direct)
+}
+
+/**
+ * Encode comparisons of integer variables using the order encoding:
+ * ```text
+ * x = 2  ~> and([x#order_2, not x#order_3])   (x in 1..3)
+ * x <= 2 ~> not x#order_3
+ * ```
+ */
+#[register_rule(("SAT_Order", 9000))]
+fn int_to_order(expr: &Expr, mdl: &Model) -> ApplicationResult {
+    encode_comparison(expr, mdl, IntEncoding::Order)
+}
+
+/**
+ * Encode comparisons of integer variables using the log encoding:
+ * ```text
+ * x = 2  ~> and([x#log_0, not x#log_1])       (x in 1..3)
+ * x <= 2 ~> not x#log_1
+ * ```
+ */
+#[register_rule(("SAT_Log", 9000))]
+fn int_to_log(expr: &Expr, mdl: &Model) -> ApplicationResult {
+    encode_comparison(expr, mdl, IntEncoding::Log)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum IntEncoding {
+    Direct,
+    Order,
+    Log,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+enum Rel {
+    Eq,
+    Neq,
+    Lt,
+    Leq,
+    Gt,
+    Geq,
+}
+
+impl Rel {
+    /// The relation with its arguments swapped: `a R b` iff `b R.flip() a`.
+    fn flip(self) -> Rel {
+        match self {
+            Rel::Lt => Rel::Gt,
+            Rel::Leq => Rel::Geq,
+            Rel::Gt => Rel::Lt,
+            Rel::Geq => Rel::Leq,
+            x => x,
+        }
+    }
+}
+
+/// An operand of a comparison.
+enum Operand {
+    /// An integer variable, with the values in its domain in ascending order.
+    Var(Name, Vec<i32>),
+    Const(i32),
+}
+
+fn encode_comparison(expr: &Expr, mdl: &Model, encoding: IntEncoding) -> ApplicationResult {
+    let (rel, a, b) = match expr {
+        Expr::Eq(_, a, b) => (Rel::Eq, a, b),
+        Expr::Neq(_, a, b) => (Rel::Neq, a, b),
+        Expr::Lt(_, a, b) => (Rel::Lt, a, b),
+        Expr::Leq(_, a, b) => (Rel::Leq, a, b),
+        Expr::Gt(_, a, b) => (Rel::Gt, a, b),
+        Expr::Geq(_, a, b) => (Rel::Geq, a, b),
+        _ => return Err(ApplicationError::RuleNotApplicable),
+    };
+
+    let (a, b) = (to_operand(a, mdl)?, to_operand(b, mdl)?);
+    let new_expr = match (&a, &b) {
+        (Operand::Var(x, xs), Operand::Const(c)) => compare(encoding, x, xs, rel, *c),
+        (Operand::Const(c), Operand::Var(x, xs)) => compare(encoding, x, xs, rel.flip(), *c),
+
+        // x R y iff for each value v of x, x = v -> v R y
+        (Operand::Var(x, xs), Operand::Var(y, ys)) => and(xs
+            .iter()
+            .map(|&v| {
+                or(vec![
+                    not(equals(encoding, x, xs, v)),
+                    compare(encoding, y, ys, rel.flip(), v),
+                ])
+            })
+            .collect()),
+        (Operand::Const(_), Operand::Const(_)) => return Err(ApplicationError::RuleNotApplicable),
+    };
+
+    // add the representation of each variable, the first time it is encoded.
+    let mut new_vars = SymbolTable::new();
+    let mut new_top = Vec::new();
+    for operand in [&a, &b] {
+        if let Operand::Var(x, xs) = operand {
+            let (bits, constraints) = representation(encoding, x, xs);
+            if bits.iter().any(|bit| mdl.variables.contains_key(bit)) {
+                continue;
+            }
+            for bit in bits {
+                new_vars.insert(bit, DecisionVariable::new(Domain::BoolDomain));
+            }
+            new_top.extend(constraints);
+        }
+    }
+
+    let new_top = if new_top.is_empty() {
+        Expr::Nothing
+    } else {
+        Expr::And(Metadata::new(), new_top)
+    };
+
+    Ok(Reduction::new(new_expr, new_top, new_vars))
+}
+
+fn to_operand(expr: &Expr, mdl: &Model) -> Result<Operand, ApplicationError> {
+    match expr {
+        Expr::Constant(_, Const::Int(c)) => Ok(Operand::Const(*c)),
+        Expr::Reference(_, name) => {
+            let mut values = mdl
+                .variables
+                .get(name)
+                .and_then(|var| var.domain.values_i32())
+                .ok_or(ApplicationError::RuleNotApplicable)?;
+            values.sort();
+            values.dedup();
+
+            if values.is_empty() {
+                return Err(ApplicationError::DomainError);
+            }
+            Ok(Operand::Var(name.clone(), values))
+        }
+        _ => Err(ApplicationError::RuleNotApplicable),
+    }
+}
+
+/// The variables representing `x`, and the constraints that make sure that they represent a value
+/// in its domain.
+fn representation(encoding: IntEncoding, x: &Name, xs: &[i32]) -> (Vec<Name>, Vec<Expr>) {
+    match encoding {
+        IntEncoding::Direct => {
+            let bits: Vec<Name> = xs.iter().map(|&v| bit(x, encoding, v)).collect();
+
+            // exactly one value: at least one, and at most one, which the SAT adaptor gives an
+            // at-most-one encoding.
+            let constraints = vec![
+                Expr::Or(Metadata::new(), bits.iter().map(var).collect()),
+                Expr::SumLeq(
+                    Metadata::new(),
+                    bits.iter().map(var).collect(),
+                    Box::new(Expr::Constant(Metadata::new(), Const::Int(1))),
+                ),
+            ];
+
+            (bits, constraints)
+        }
+        IntEncoding::Order => {
+            let bits: Vec<Name> = xs[1..].iter().map(|&v| bit(x, encoding, v)).collect();
+
+            // x >= v_(i+1) -> x >= v_i
+            let constraints = bits
+                .windows(2)
+                .map(|w| Expr::Or(Metadata::new(), vec![not(var(&w[1])), var(&w[0])]))
+                .collect();
+
+            (bits, constraints)
+        }
+        IntEncoding::Log => {
+            let (lb, n_bits) = log_bits(xs);
+            let bits: Vec<Name> = (0..n_bits).map(|i| bit(x, encoding, i)).collect();
+
+            // rule out the values that the bits can represent, but are not in the domain.
+            let constraints = (0..(1_i64 << n_bits))
+                .map(|k| lb as i64 + k)
+                .filter(|&v| xs.binary_search(&(v as i32)).is_err())
+                .map(|v| {
+                    let k = v - lb as i64;
+                    let lits = bits
+                        .iter()
+                        .enumerate()
+                        .map(|(i, b)| match (k >> i) & 1 {
+                            1 => not(var(b)),
+                            _ => var(b),
+                        })
+                        .collect();
+                    Expr::Or(Metadata::new(), lits)
+                })
+                .collect();
+
+            (bits, constraints)
+        }
+    }
+}
+
+/// A formula that is true iff `x R c`.
+fn compare(encoding: IntEncoding, x: &Name, xs: &[i32], rel: Rel, c: i32) -> Expr {
+    match rel {
+        Rel::Eq => equals(encoding, x, xs, c),
+        Rel::Neq => not(equals(encoding, x, xs, c)),
+        Rel::Leq => at_most(encoding, x, xs, c as i64),
+        Rel::Lt => at_most(encoding, x, xs, c as i64 - 1),
+        Rel::Geq => not(at_most(encoding, x, xs, c as i64 - 1)),
+        Rel::Gt => not(at_most(encoding, x, xs, c as i64)),
+    }
+}
+
+/// A formula that is true iff `x = c`.
+fn equals(encoding: IntEncoding, x: &Name, xs: &[i32], c: i32) -> Expr {
+    if xs.binary_search(&c).is_err() {
+        return bool_const(false);
+    }
+
+    match encoding {
+        IntEncoding::Direct => var(&bit(x, encoding, c)),
+        IntEncoding::Order => and(vec![
+            order_at_least(x, xs, c as i64),
+            not(order_at_least(x, xs, c as i64 + 1)),
+        ]),
+        IntEncoding::Log => {
+            let (lb, n_bits) = log_bits(xs);
+            let k = c as i64 - lb as i64;
+            and((0..n_bits)
+                .map(|i| {
+                    let b = var(&bit(x, encoding, i));
+                    match (k >> i) & 1 {
+                        1 => b,
+                        _ => not(b),
+                    }
+                })
+                .collect())
+        }
+    }
+}
+
+/// A formula that is true iff `x <= c`.
+fn at_most(encoding: IntEncoding, x: &Name, xs: &[i32], c: i64) -> Expr {
+    match encoding {
+        IntEncoding::Direct => or(xs
+            .iter()
+            .filter(|&&v| v as i64 <= c)
+            .map(|&v| var(&bit(x, encoding, v)))
+            .collect()),
+        IntEncoding::Order => not(order_at_least(x, xs, c + 1)),
+        IntEncoding::Log => {
+            let (lb, n_bits) = log_bits(xs);
+            let k = c - lb as i64;
+            if k < 0 {
+                return bool_const(false);
+            }
+            if k >= (1_i64 << n_bits) - 1 {
+                return bool_const(true);
+            }
+
+            // compare the bits with k, from the least significant bit upwards:
+            // (x <= k on bits 0..=i) iff (x_i < k_i) or (x_i = k_i and x <= k on bits 0..i).
+            let mut formula = bool_const(true);
+            for i in 0..n_bits {
+                let b = var(&bit(x, encoding, i));
+                formula = match (k >> i) & 1 {
+                    1 => or(vec![not(b), formula]),
+                    _ => and(vec![not(b), formula]),
+                };
+            }
+            formula
+        }
+    }
+}
+
+/// In the order encoding, a literal that is true iff `x >= c`.
+fn order_at_least(x: &Name, xs: &[i32], c: i64) -> Expr {
+    match xs.iter().find(|&&v| v as i64 >= c) {
+        None => bool_const(false),
+        Some(&v) if v == xs[0] => bool_const(true),
+        Some(&v) => var(&bit(x, IntEncoding::Order, v)),
+    }
+}
+
+/// The smallest value of `x`, and the number of bits needed to represent `x` in the log encoding.
+fn log_bits(xs: &[i32]) -> (i32, i32) {
+    let lb = xs[0];
+    let range = xs[xs.len() - 1] as i64 - lb as i64;
+    (lb, (i64::BITS - range.leading_zeros()) as i32)
+}
+
+fn bit(x: &Name, encoding: IntEncoding, i: i32) -> Name {
+    let repr = match encoding {
+        IntEncoding::Direct => "direct",
+        IntEncoding::Order => "order",
+        IntEncoding::Log => "log",
+    };
+    Name::RepresentedName(Box::new(x.clone()), repr.to_owned(), i)
+}
+
+fn var(name: &Name) -> Expr {
+    Expr::Reference(Metadata::new(), name.clone())
+}
+
+/// The negation of a formula, with constants and double negations removed.
+fn not(expr: Expr) -> Expr {
+    match expr {
+        Expr::Constant(_, Const::Bool(val)) => bool_const(!val),
+        Expr::Not(_, e) => *e,
+        e => Expr::Not(Metadata::new(), Box::new(e)),
+    }
+}
+
+/// The conjunction of some formulas, with constants removed.
+///
+/// Empty conjunctions are removed by the rewriter, so these are replaced by `true`.
+fn and(exprs: Vec<Expr>) -> Expr {
+    let mut new_exprs = Vec::new();
+    for e in exprs {
+        match e {
+            Expr::Constant(_, Const::Bool(true)) => {}
+            Expr::Constant(_, Const::Bool(false)) => return bool_const(false),
+            e => new_exprs.push(e),
+        }
+    }
+
+    match new_exprs.len() {
+        0 => bool_const(true),
+        1 => new_exprs.remove(0),
+        _ => Expr::And(Metadata::new(), new_exprs),
+    }
+}
+
+/// The disjunction of some formulas, with constants removed.
+fn or(exprs: Vec<Expr>) -> Expr {
+    let mut new_exprs = Vec::new();
+    for e in exprs {
+        match e {
+            Expr::Constant(_, Const::Bool(false)) => {}
+            Expr::Constant(_, Const::Bool(true)) => return bool_const(true),
+            e => new_exprs.push(e),
+        }
+    }
+
+    match new_exprs.len() {
+        0 => bool_const(false),
+        1 => new_exprs.remove(0),
+        _ => Expr::Or(Metadata::new(), new_exprs),
+    }
+}
+
+fn bool_const(val: bool) -> Expr {
+    Expr::Constant(Metadata::new(), Const::Bool(val))
+}
@@ -0,0 +1,103 @@
+/***********************************************************************************/
+/*        This file contains rules for lowering boolean sums for SAT              */
+/***********************************************************************************/
+
+use conjure_core::ast::{Constant as Const, Domain, Expression as Expr};
+use conjure_core::metadata::Metadata;
+use conjure_core::rule_engine::{register_rule, ApplicationError, ApplicationResult, Reduction};
+use conjure_core::Model;
+
+/**
+ * Convert comparisons between a sum of booleans and a constant to `sum_leq` and `sum_geq`, which
+ * the SAT adaptor encodes using a cardinality or pseudo-boolean encoding:
+ * ```text
+ * sum([a, b, c]) <= 2 ~> sum_leq([a, b, c], 2)
+ * sum([a, b, c]) > 1  ~> sum_geq([a, b, c], 2)
+ * 2 = sum([a, b, c])  ~> and([sum_leq([a, b, c], 2), sum_geq([a, b, c], 2)])
+ * ```
+ *
+ * Weighted sums are given by repeating terms, so `sum([a, a, b])` is `2a + b`. The SAT adaptor
+ * merges repeated terms, so the encoder is given `a` once, with weight 2.
+ */
+#[register_rule(("CNF", 8600))]
+fn bool_sum_to_pb(expr: &Expr, mdl: &Model) -> ApplicationResult {
+    // swap the arguments of >= and >, to get <= and <.
+    let (metadata, a, b) = match expr {
+        Expr::Eq(md, a, b) | Expr::Leq(md, a, b) | Expr::Lt(md, a, b) => (md, a, b),
+        Expr::Geq(md, a, b) | Expr::Gt(md, a, b) => (md, b, a),
+        _ => return Err(ApplicationError::RuleNotApplicable),
+    };
+
+    // sum <= k, or k <= sum
+    let (exprs, k, sum_on_left) = match (a.as_ref(), b.as_ref()) {
+        (Expr::Sum(_, exprs), Expr::Constant(_, Const::Int(k))) => (exprs, *k, true),
+        (Expr::Constant(_, Const::Int(k)), Expr::Sum(_, exprs)) => (exprs, *k, false),
+        _ => return Err(ApplicationError::RuleNotApplicable),
+    };
+
+    if !exprs.iter().all(|e| is_bool_term(e, mdl)) {
+        return Err(ApplicationError::RuleNotApplicable);
+    }
+
+    // strict inequalities are made non-strict by moving the bound.
+    let k = match (expr, sum_on_left) {
+        (Expr::Lt(_, _, _) | Expr::Gt(_, _, _), true) => k - 1,
+        (Expr::Lt(_, _, _) | Expr::Gt(_, _, _), false) => k + 1,
+        _ => k,
+    };
+
+    let leq = Expr::SumLeq(
+        metadata.clone_dirty(),
+        exprs.clone(),
+        Box::new(Expr::Constant(Metadata::new(), Const::Int(k))),
+    );
+    let geq = Expr::SumGeq(
+        metadata.clone_dirty(),
+        exprs.clone(),
+        Box::new(Expr::Constant(Metadata::new(), Const::Int(k))),
+    );
+
+    let new_expr = match expr {
+        Expr::Eq(_, _, _) => Expr::And(metadata.clone_dirty(), vec![leq, geq]),
+        _ if sum_on_left => leq,
+        _ => geq,
+    };
+
+    Ok(Reduction::pure(new_expr))
+}
+
+/**
+ * Convert a `sum_eq` of booleans to a `sum_leq` and a `sum_geq`:
+ * ```text
+ * sum_eq([a, b, c], 2) ~> and([sum_leq([a, b, c], 2), sum_geq([a, b, c], 2)])
+ * ```
+ */
+#[register_rule(("CNF", 8600))]
+fn bool_sum_eq_to_pb(expr: &Expr, mdl: &Model) -> ApplicationResult {
+    match expr {
+        Expr::SumEq(metadata, exprs, k) if exprs.iter().all(|e| is_bool_term(e, mdl)) => {
+            Ok(Reduction::pure(Expr::And(
+                metadata.clone_dirty(),
+                vec![
+                    Expr::SumLeq(Metadata::new(), exprs.clone(), k.clone()),
+                    Expr::SumGeq(Metadata::new(), exprs.clone(), k.clone()),
+                ],
+            )))
+        }
+        _ => Err(ApplicationError::RuleNotApplicable),
+    }
+}
+
+/// Whether the given expression can be a term in a pseudo-boolean constraint: a boolean literal or
+/// a constant.
+fn is_bool_term(expr: &Expr, mdl: &Model) -> bool {
+    match expr {
+        Expr::Constant(_, _) => true,
+        Expr::Reference(_, name) => mdl
+            .variables
+            .get(name)
+            .is_some_and(|var| var.domain == Domain::BoolDomain),
+        Expr::Not(_, e) => matches!(e.as_ref(), Expr::Reference(_, _)) && is_bool_term(e, mdl),
+        _ => false,
+    }
+}
@@ -0,0 +1,216 @@
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use crate::ast::{Constant, Domain, Expression, Name};
+use crate::solver::{SolverCallback, SolverFamily, SolverMutCallback};
+use crate::stats::SolverStats;
+use crate::Model as ConjureModel;
+
+use super::super::model_modifier::NotModifiable;
+use super::super::private;
+use super::super::SearchComplete::*;
+use super::super::SearchIncomplete::*;
+use super::super::SearchStatus::*;
+use super::super::SolveSuccess;
+use super::super::SolverAdaptor;
+use super::super::SolverError;
+use super::super::SolverError::*;
+
+/// A [SolverAdaptor] that tries every assignment of the variables in a model, checking the
+/// constraints of each using [`Expression::eval`].
+///
+/// This does not need the model to be rewritten for any solver, and does not depend on any
+/// native solver, so it is useful as a reference when testing rules and other adaptors. As the
+/// number of assignments grows exponentially with the number of variables, it is only suitable for
+/// small models.
+pub struct BruteForce {
+    __non_constructable: private::Internal,
+    variables: Option<Vec<(Name, Vec<Constant>)>>,
+    constraints: Option<Vec<Expression>>,
+    time_limit: Option<Duration>,
+    max_assignments: u64,
+}
+
+impl private::Sealed for BruteForce {}
+
+impl Default for BruteForce {
+    fn default() -> Self {
+        BruteForce {
+            __non_constructable: private::Internal,
+            variables: None,
+            constraints: None,
+            time_limit: None,
+            max_assignments: 1 << 24,
+        }
+    }
+}
+
+impl BruteForce {
+    pub fn new() -> BruteForce {
+        BruteForce::default()
+    }
+
+    /// Sets the largest number of assignments that a model can have. Loading a model with more
+    /// assignments fails.
+    pub fn with_max_assignments(self, max_assignments: u64) -> Self {
+        BruteForce {
+            max_assignments,
+            ..self
+        }
+    }
+
+    /// Tries every assignment in turn, calling `on_solution` for each solution.
+    fn search(
+        &self,
+        mut on_solution: impl FnMut(HashMap<Name, Constant>) -> bool,
+    ) -> Result<SolveSuccess, SolverError> {
+        #[allow(clippy::expect_used)]
+        let variables = self.variables.as_ref().expect("STATE MACHINE ERR");
+        #[allow(clippy::expect_used)]
+        let constraints = self.constraints.as_ref().expect("STATE MACHINE ERR");
+
+        let start_time = Instant::now();
+        let mut n_assignments: u64 = 0;
+        let mut n_solutions: u64 = 0;
+
+        // the index of the current value of each variable.
+        let mut indices = vec![0usize; variables.len()];
+        let status = loop {
+            if self.time_limit.is_some_and(|x| start_time.elapsed() >= x) {
+                break Incomplete(Timeout);
+            }
+
+            let assignment: HashMap<Name, Constant> = variables
+                .iter()
+                .zip(&indices)
+                .map(|((name, values), &i)| (name.clone(), values[i].clone()))
+                .collect();
+            n_assignments += 1;
+
+            let mut satisfied = true;
+            for constraint in constraints {
+                match constraint.eval(&assignment) {
+                    Ok(Constant::Bool(true)) | Ok(Constant::Int(1)) => {}
+                    Ok(_) => {
+                        satisfied = false;
+                        break;
+                    }
+                    Err(e) => {
+                        return Err(ModelInvalid(format!(
+                            "could not evaluate {}: {}",
+                            constraint, e
+                        )))
+                    }
+                }
+            }
+
+            if satisfied {
+                n_solutions += 1;
+                if !on_solution(assignment) {
+                    break Incomplete(UserTerminated);
+                }
+            }
+
+            if !next_assignment(&mut indices, variables) {
+                break match n_solutions {
+                    0 => Complete(NoSolutions),
+                    _ => Complete(HasSolutions),
+                };
+            }
+        };
+
+        let stats = SolverStats {
+            nodes: Some(n_assignments),
+            satisfiable: Some(n_solutions > 0),
+            solutions_found: Some(n_solutions),
+            solver_solve_time: Some(start_time.elapsed().as_secs_f64()),
+            timed_out: Some(status == Incomplete(Timeout)),
+            ..Default::default()
+        };
+        Ok(SolveSuccess { stats, status })
+    }
+}
+
+/// Moves to the next assignment, incrementing the index of the last variable's value and carrying
+/// over like an odometer. Returns `false` once every assignment has been tried.
+fn next_assignment(indices: &mut [usize], variables: &[(Name, Vec<Constant>)]) -> bool {
+    for (i, (_, values)) in indices.iter_mut().zip(variables).rev() {
+        *i += 1;
+        if *i < values.len() {
+            return true;
+        }
+        *i = 0;
+    }
+    false
+}
+
+impl SolverAdaptor for BruteForce {
+    fn solve(
+        &mut self,
+        callback: SolverCallback,
+        _: private::Internal,
+    ) -> Result<SolveSuccess, SolverError> {
+        self.search(callback)
+    }
+
+    /// Solves as [`solve`](SolverAdaptor::solve) does: the model cannot be modified during search.
+    fn solve_mut(
+        &mut self,
+        callback: SolverMutCallback,
+        _: private::Internal,
+    ) -> Result<SolveSuccess, SolverError> {
+        self.search(|solution| callback(solution, Box::new(NotModifiable)))
+    }
+
+    fn load_model(&mut self, model: ConjureModel, _: private::Internal) -> Result<(), SolverError> {
+        let mut variables: Vec<(Name, Vec<Constant>)> = Vec::new();
+        let mut n_assignments: u64 = 1;
+        for (name, var) in &model.variables {
+            let values: Vec<Constant> = match &var.domain {
+                Domain::BoolDomain => vec![Constant::Bool(false), Constant::Bool(true)],
+                Domain::IntDomain(_) => {
+                    // ranges may overlap, so take each value once.
+                    #[allow(clippy::expect_used)]
+                    let mut values = var.domain.values_i32().expect("an integer domain");
+                    values.sort();
+                    values.dedup();
+                    values.into_iter().map(Constant::Int).collect()
+                }
+            };
+
+            n_assignments = n_assignments
+                .checked_mul(values.len() as u64)
+                .filter(|&x| x <= self.max_assignments)
+                .ok_or(ModelFeatureNotSupported(format!(
+                    "the model has more than {} assignments",
+                    self.max_assignments
+                )))?;
+            variables.push((name.clone(), values));
+        }
+
+        // a variable with an empty domain leaves no assignments to try.
+        if n_assignments == 0 {
+            variables.clear();
+            self.constraints = Some(vec![false.into()]);
+        } else {
+            self.constraints = Some(model.get_constraints_vec());
+        }
+
+        // enumerate in the same order each time.
+        variables.sort_by(|(a, _), (b, _)| a.cmp(b));
+        self.variables = Some(variables);
+        Ok(())
+    }
+
+    fn set_time_limit(&mut self, time_limit: Duration, _: private::Internal) {
+        self.time_limit = Some(time_limit);
+    }
+
+    fn get_family(&self) -> SolverFamily {
+        SolverFamily::BruteForce
+    }
+
+    fn get_name(&self) -> Option<String> {
+        Some("Brute force".to_owned())
+    }
+}
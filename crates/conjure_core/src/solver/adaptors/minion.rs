@@ -1,12 +1,17 @@
-use std::collections::HashMap;
-use std::sync::{Mutex, OnceLock};
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::ops::Add;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
 
 use regex::Regex;
 
 use minion_ast::Model as MinionModel;
 use minion_rs::ast as minion_ast;
 use minion_rs::error::MinionError;
-use minion_rs::{get_from_table, run_minion};
+use minion_rs::print::write_minion_file;
+use minion_rs::worker::WorkerPool;
+use minion_rs::{get_from_table, run_minion_with_options, SearchOptions};
 
 use crate::ast as conjure_ast;
 use crate::solver::SolverCallback;
@@ -15,10 +20,11 @@ use crate::solver::SolverMutCallback;
 use crate::stats::SolverStats;
 use crate::Model as ConjureModel;
 
-use super::super::model_modifier::NotModifiable;
+use super::super::model_modifier::{ModelModifier, ModificationFailure};
 use super::super::private;
 use super::super::SearchComplete::*;
 use super::super::SearchIncomplete::*;
+use super::super::SearchStatus;
 use super::super::SearchStatus::*;
 use super::super::SolveSuccess;
 use super::super::SolverAdaptor;
@@ -31,20 +37,23 @@ use super::super::SolverError::*;
 pub struct Minion {
     __non_constructable: private::Internal,
     model: Option<MinionModel>,
+    search_options: SearchOptions,
+    time_limit: Option<Duration>,
+    worker_pool: Option<Arc<WorkerPool>>,
 }
 
+// Minion keeps its search state and run statistics in globals, so is not reentrant: only one
+// solve can run in this process at a time, and its statistics must be read before another starts.
+//
+// To run solves in parallel, give each Minion adaptor a worker pool (see
+// [Minion::with_worker_pool]); each solve then runs in its own worker process, without this lock.
 static MINION_LOCK: Mutex<()> = Mutex::new(());
-static USER_CALLBACK: OnceLock<Mutex<SolverCallback>> = OnceLock::new();
-static ANY_SOLUTIONS: Mutex<bool> = Mutex::new(false);
-static USER_TERMINATED: Mutex<bool> = Mutex::new(false);
 
 #[allow(clippy::unwrap_used)]
-fn minion_rs_callback(solutions: HashMap<minion_ast::VarName, minion_ast::Constant>) -> bool {
-    *(ANY_SOLUTIONS.lock().unwrap()) = true;
-    let callback = USER_CALLBACK
-        .get_or_init(|| Mutex::new(Box::new(|x| true)))
-        .lock()
-        .unwrap();
+fn minion_solutions_to_conjure(
+    solutions: HashMap<minion_ast::VarName, minion_ast::Constant>,
+) -> HashMap<conjure_ast::Name, conjure_ast::Constant> {
+    let machine_name_re = Regex::new(r"__conjure_machine_name_([0-9]+)").unwrap();
 
     let mut conjure_solutions: HashMap<conjure_ast::Name, conjure_ast::Constant> = HashMap::new();
     for (minion_name, minion_const) in solutions.into_iter() {
@@ -54,7 +63,6 @@ fn minion_rs_callback(solutions: HashMap<minion_ast::VarName, minion_ast::Consta
             _ => todo!(),
         };
 
-        let machine_name_re = Regex::new(r"__conjure_machine_name_([0-9]+)").unwrap();
         let conjure_name = if let Some(caps) = machine_name_re.captures(&minion_name) {
             conjure_ast::Name::MachineName(caps[1].parse::<i32>().unwrap())
         } else {
@@ -64,21 +72,76 @@ fn minion_rs_callback(solutions: HashMap<minion_ast::VarName, minion_ast::Consta
         conjure_solutions.insert(conjure_name, conjure_const);
     }
 
-    let continue_search = (**callback)(conjure_solutions);
-    if !continue_search {
-        *(USER_TERMINATED.lock().unwrap()) = true;
-    }
-
-    continue_search
+    conjure_solutions
 }
 
 impl private::Sealed for Minion {}
 
 impl Minion {
     pub fn new() -> Minion {
+        Minion::with_search_options(SearchOptions::default())
+    }
+
+    /// Creates a Minion adaptor that runs Minion with the given [SearchOptions].
+    pub fn with_search_options(search_options: SearchOptions) -> Minion {
         Minion {
             __non_constructable: private::Internal,
             model: None,
+            search_options,
+            time_limit: None,
+            worker_pool: None,
+        }
+    }
+
+    /// Runs Minion in a process from the given [WorkerPool], instead of in this process.
+    ///
+    /// Minion can only run one model at a time per process, so this allows several solves to run
+    /// at once.
+    pub fn with_worker_pool(mut self, worker_pool: Arc<WorkerPool>) -> Minion {
+        self.worker_pool = Some(worker_pool);
+        self
+    }
+
+    /// The search options to use for a run with the given time limit.
+    fn search_options_with_time_limit(&self, time_limit: Option<Duration>) -> SearchOptions {
+        // Minion's time limit is in whole seconds, so round up, as documented on
+        // SolveOptions::time_limit.
+        let mut search_options = self.search_options.clone();
+        if let Some(time_limit) = time_limit {
+            let time_limit_s = time_limit.as_secs() + u64::from(time_limit.subsec_nanos() > 0);
+            search_options.time_limit_s = Some(match search_options.time_limit_s {
+                Some(x) => x.min(time_limit_s),
+                None => time_limit_s,
+            });
+        }
+        search_options
+    }
+
+    /// Runs Minion on the given model, either in this process or in the worker pool.
+    fn run(
+        &self,
+        model: &MinionModel,
+        search_options: &SearchOptions,
+        on_solution: impl FnMut(HashMap<minion_ast::VarName, minion_ast::Constant>) -> bool,
+    ) -> Result<SolverStats, SolverError> {
+        match &self.worker_pool {
+            Some(worker_pool) => {
+                let table = worker_pool
+                    .run(model, search_options, on_solution)
+                    .map_err(minion_error_to_solver_error)?;
+                Ok(get_solver_stats(search_options, &|key| {
+                    table.get(key).cloned()
+                }))
+            }
+            None => {
+                #[allow(clippy::unwrap_used)]
+                let _minion_lock = MINION_LOCK.lock().unwrap();
+                run_minion_with_options(model.clone(), search_options, on_solution)
+                    .map_err(minion_error_to_solver_error)?;
+                Ok(get_solver_stats(search_options, &|key| {
+                    get_from_table(key.into())
+                }))
+            }
         }
     }
 }
@@ -90,65 +153,122 @@ impl Default for Minion {
 }
 
 impl SolverAdaptor for Minion {
-    #[allow(clippy::unwrap_used)]
     fn solve(
         &mut self,
         callback: SolverCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
-        // our minion callback is global state, so single threading the adaptor as a whole is
-        // probably a good move...
-        #[allow(clippy::unwrap_used)]
-        let mut minion_lock = MINION_LOCK.lock().unwrap();
-
-        #[allow(clippy::unwrap_used)]
-        let mut user_callback = USER_CALLBACK
-            .get_or_init(|| Mutex::new(Box::new(|x| true)))
-            .lock()
-            .unwrap();
-        *user_callback = callback;
-        drop(user_callback); // release mutex. REQUIRED so that run_minion can use the
-                             // user callback and not deadlock.
-
-        run_minion(
-            self.model.clone().expect("STATE MACHINE ERR"),
-            minion_rs_callback,
-        )
-        .map_err(|err| match err {
-            MinionError::RuntimeError(x) => Runtime(format!("{:#?}", x)),
-            MinionError::Other(x) => Runtime(format!("{:#?}", x)),
-            MinionError::NotImplemented(x) => RuntimeNotImplemented(x),
-            x => Runtime(format!("unknown minion_rs error: {:#?}", x)),
+        #[allow(clippy::expect_used)]
+        let model = self.model.as_ref().expect("STATE MACHINE ERR");
+        let search_options = self.search_options_with_time_limit(self.time_limit);
+
+        let mut any_solutions = false;
+        let mut user_terminated = false;
+
+        let stats = self.run(model, &search_options, |solutions| {
+            any_solutions = true;
+            let continue_search = callback(minion_solutions_to_conjure(solutions));
+            if !continue_search {
+                user_terminated = true;
+            }
+            continue_search
         })?;
 
-        let mut status = Complete(HasSolutions);
-        if *(USER_TERMINATED.lock()).unwrap() {
-            status = Incomplete(UserTerminated);
-        } else if *(ANY_SOLUTIONS.lock()).unwrap() {
-            status = Complete(NoSolutions);
-        }
-        Ok(SolveSuccess {
-            stats: get_solver_stats(),
-            status,
-        })
+        let status = search_status(any_solutions, user_terminated, &stats);
+        Ok(SolveSuccess { stats, status })
     }
 
+    /// Runs Minion, restarting search whenever the model is modified in the callback.
+    ///
+    /// Modifications made through the [`ModelModifier`] are recorded, and once the callback
+    /// returns, search is restarted with the extended model. Search finishes once a run completes
+    /// without the model being modified, or the callback returns `false`.
+    ///
+    /// A restarted search finds again any solutions found before the restart that are still
+    /// solutions of the modified model. These are only given to the callback once.
+    ///
+    /// The time limit, if any, applies to the search as a whole.
     fn solve_mut(
         &mut self,
         callback: SolverMutCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
-        Err(OpNotImplemented("solve_mut".into()))
+        #[allow(clippy::expect_used)]
+        let mut model = Arc::new(self.model.clone().expect("STATE MACHINE ERR"));
+        let deadline = self.time_limit.map(|x| Instant::now() + x);
+
+        let mut any_solutions = false;
+        let mut user_terminated = false;
+        let mut stats: Option<SolverStats> = None;
+        let mut seen: BTreeSet<BTreeMap<conjure_ast::Name, conjure_ast::Constant>> =
+            BTreeSet::new();
+
+        loop {
+            let time_limit = deadline.map(|x| x.saturating_duration_since(Instant::now()));
+            if time_limit.is_some_and(|x| x.is_zero()) {
+                stats = stats.map(|x| SolverStats {
+                    timed_out: Some(true),
+                    ..x
+                });
+                break;
+            }
+
+            let search_options = self.search_options_with_time_limit(time_limit);
+            let modified: Arc<Mutex<Option<MinionModel>>> = Arc::new(Mutex::new(None));
+
+            let run_stats = self.run(&model, &search_options, |solutions| {
+                any_solutions = true;
+                let solutions = minion_solutions_to_conjure(solutions);
+                if !seen.insert(solutions.clone().into_iter().collect()) {
+                    return true;
+                }
+
+                let modifier = MinionModelModifier {
+                    model: model.clone(),
+                    modified: modified.clone(),
+                };
+
+                if !callback(solutions, Box::new(modifier)) {
+                    user_terminated = true;
+                    return false;
+                }
+
+                // restart search with the modified model.
+                #[allow(clippy::unwrap_used)]
+                modified.lock().unwrap().is_none()
+            })?;
+
+            stats = Some(match stats {
+                Some(x) => add_run_stats(x, run_stats),
+                None => run_stats,
+            });
+
+            #[allow(clippy::unwrap_used)]
+            let modified = modified.lock().unwrap().take();
+            match modified {
+                Some(new_model) if !user_terminated => model = Arc::new(new_model),
+                _ => break,
+            }
+        }
+
+        let stats = stats.unwrap_or_default();
+        let status = search_status(any_solutions, user_terminated, &stats);
+        Ok(SolveSuccess { stats, status })
     }
 
     fn load_model(&mut self, model: ConjureModel, _: private::Internal) -> Result<(), SolverError> {
         let mut minion_model = MinionModel::new();
         parse_vars(&model, &mut minion_model)?;
         parse_exprs(&model, &mut minion_model)?;
+        parse_search_order(&model, &mut minion_model);
         self.model = Some(minion_model);
         Ok(())
     }
 
+    fn set_time_limit(&mut self, time_limit: Duration, _: private::Internal) {
+        self.time_limit = Some(time_limit);
+    }
+
     fn get_family(&self) -> SolverFamily {
         SolverFamily::Minion
     }
@@ -156,6 +276,15 @@ impl SolverAdaptor for Minion {
     fn get_name(&self) -> Option<String> {
         Some("Minion".to_owned())
     }
+
+    fn write_solver_input_file(
+        &self,
+        writer: &mut impl std::io::Write,
+    ) -> Result<(), std::io::Error> {
+        #[allow(clippy::expect_used)]
+        let model = self.model.as_ref().expect("STATE MACHINE ERR");
+        write_minion_file(writer, model)
+    }
 }
 
 fn parse_vars(
@@ -171,6 +300,25 @@ fn parse_vars(
     Ok(())
 }
 
+/// Searches on, and returns, only the variables declared by the user.
+///
+/// Variables introduced during rewriting are left as auxiliary variables. Variables are searched
+/// on in name order, so that the search order does not depend on the order of the symbol table.
+fn parse_search_order(conjure_model: &ConjureModel, minion_model: &mut MinionModel) {
+    let mut primary_vars: Vec<minion_ast::VarName> = conjure_model
+        .variables
+        .keys()
+        .filter(|name| matches!(name, conjure_ast::Name::UserName(_)))
+        .map(|name| _name_to_string(name.to_owned()))
+        .collect();
+    primary_vars.sort();
+
+    minion_model
+        .search_orders
+        .push(minion_ast::SearchOrder::new(primary_vars.clone()));
+    minion_model.print_vars = Some(primary_vars);
+}
+
 fn parse_var(
     name: &conjure_ast::Name,
     var: &conjure_ast::DecisionVariable,
@@ -391,13 +539,154 @@ fn _name_to_string(name: conjure_ast::Name) -> String {
     match name {
         conjure_ast::Name::UserName(x) => x,
         conjure_ast::Name::MachineName(x) => format!("__conjure_machine_name_{}", x),
+        conjure_ast::Name::RepresentedName(x, repr, i) => format!(
+            "__conjure_represented_name_{}_{}_{}",
+            _name_to_string(*x),
+            repr,
+            i
+        ),
     }
 }
 
-#[allow(clippy::unwrap_used)]
-fn get_solver_stats() -> SolverStats {
+/// A [`ModelModifier`] for Minion, used by [`Minion::solve_mut`](SolverAdaptor::solve_mut).
+///
+/// This records modifications in a copy of the current model, which is used when search is
+/// restarted.
+struct MinionModelModifier {
+    model: Arc<MinionModel>,
+    modified: Arc<Mutex<Option<MinionModel>>>,
+}
+
+impl MinionModelModifier {
+    /// Applies `f` to the modified model, leaving it unchanged if `f` fails.
+    fn modify(
+        &self,
+        f: impl FnOnce(&mut MinionModel) -> Result<(), SolverError>,
+    ) -> Result<(), ModificationFailure> {
+        #[allow(clippy::unwrap_used)]
+        let mut modified = self.modified.lock().unwrap();
+        let mut new_model = modified.clone().unwrap_or_else(|| (*self.model).clone());
+        f(&mut new_model).map_err(|e| ModificationFailure::ArgsInvalid(e.into()))?;
+        *modified = Some(new_model);
+        Ok(())
+    }
+}
+
+impl private::Sealed for MinionModelModifier {}
+
+impl ModelModifier for MinionModelModifier {
+    fn add_constraint(
+        &self,
+        constraint: conjure_ast::Expression,
+    ) -> Result<(), ModificationFailure> {
+        self.modify(|minion_model| match constraint {
+            conjure_ast::Expression::Constant(_, conjure_ast::Constant::Bool(false)) => {
+                minion_model.constraints.push(minion_ast::Constraint::False);
+                Ok(())
+            }
+            conjure_ast::Expression::Constant(_, conjure_ast::Constant::Bool(true)) => {
+                minion_model.constraints.push(minion_ast::Constraint::True);
+                Ok(())
+            }
+            expr => parse_expr(expr, minion_model),
+        })
+    }
+
+    fn add_variable(
+        &self,
+        name: conjure_ast::Name,
+        domain: conjure_ast::Domain,
+    ) -> Result<(), ModificationFailure> {
+        self.modify(|minion_model| {
+            parse_var(
+                &name,
+                &conjure_ast::DecisionVariable::new(domain),
+                minion_model,
+            )?;
+
+            // as in parse_search_order, only search on and return variables declared by the user.
+            if let conjure_ast::Name::UserName(_) = name {
+                let var_name = _name_to_string(name);
+                if let Some(search_order) = minion_model.search_orders.first_mut() {
+                    search_order.vars.push(var_name.clone());
+                }
+                if let Some(print_vars) = &mut minion_model.print_vars {
+                    print_vars.push(var_name);
+                }
+            }
+
+            Ok(())
+        })
+    }
+}
+
+fn search_status(any_solutions: bool, user_terminated: bool, stats: &SolverStats) -> SearchStatus {
+    if user_terminated {
+        Incomplete(UserTerminated)
+    } else if stats.timed_out == Some(true) {
+        Incomplete(Timeout)
+    } else if !any_solutions {
+        Complete(NoSolutions)
+    } else {
+        Complete(HasSolutions)
+    }
+}
+
+/// Combines the stats of two consecutive runs of Minion.
+fn add_run_stats(total: SolverStats, run: SolverStats) -> SolverStats {
+    fn add<T: Add<Output = T>>(a: Option<T>, b: Option<T>) -> Option<T> {
+        match (a, b) {
+            (Some(a), Some(b)) => Some(a + b),
+            (a, b) => a.or(b),
+        }
+    }
+
     SolverStats {
-        nodes: get_from_table("Nodes".into()).map(|x| x.parse::<u64>().unwrap()),
+        nodes: add(total.nodes, run.nodes),
+        satisfiable: add_bool(total.satisfiable, run.satisfiable),
+        solver_solve_time: add(total.solver_solve_time, run.solver_solve_time),
+        solver_setup_time: add(total.solver_setup_time, run.solver_setup_time),
+        solutions_found: add(total.solutions_found, run.solutions_found),
+        ..run
+    }
+}
+
+fn add_bool(a: Option<bool>, b: Option<bool>) -> Option<bool> {
+    match (a, b) {
+        (Some(a), Some(b)) => Some(a || b),
+        (a, b) => a.or(b),
+    }
+}
+
+fn minion_error_to_solver_error(err: MinionError) -> SolverError {
+    match err {
+        // these include any diagnostics printed by Minion.
+        MinionError::RuntimeError(x) => Runtime(x.to_string()),
+        MinionError::Other(x) => Runtime(format!("{:#}", x)),
+        MinionError::NotImplemented(x) => RuntimeNotImplemented(x),
+        x => Runtime(format!("unknown minion_rs error: {:#?}", x)),
+    }
+}
+
+/// Minion's run statistics, looked up by key.
+type MinionTable<'a> = &'a dyn Fn(&str) -> Option<String>;
+
+fn get_solver_stats(search_options: &SearchOptions, table: MinionTable) -> SolverStats {
+    let solutions_found: Option<u64> = get_table_value(table, "SolutionsFound");
+    SolverStats {
+        nodes: get_table_value(table, "Nodes"),
+        satisfiable: solutions_found.map(|x| x > 0),
+        solver_solve_time: get_table_value(table, "SolveTime"),
+        solver_setup_time: get_table_value(table, "SetupTime"),
+        solutions_found,
+        timed_out: get_table_value::<u8>(table, "TimeOut").map(|x| x == 1),
+        solver_args: Some(search_options.to_args()),
         ..Default::default()
     }
 }
+
+/// Gets and parses a value from Minion's run statistics, returning `None` if it is missing or
+/// malformed.
+fn get_table_value<T: FromStr>(table: MinionTable, key: &str) -> Option<T> {
+    table(key).and_then(|x| x.trim().parse().ok())
+}
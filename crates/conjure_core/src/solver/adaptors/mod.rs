@@ -1,6 +1,8 @@
 
 #[doc(inline)]
+pub use brute_force::BruteForce;
 pub use minion::Minion;
 pub use rustsat::SAT;
+mod brute_force;
 mod minion;
 pub mod rustsat;
@@ -1,26 +1,30 @@
-use std::any::type_name;
-use std::fmt::format;
-use std::iter::Inspect;
-use std::ptr::null;
-use std::vec;
-
-use clap::error;
-use minion_rs::ast::Model;
-use rustsat::encodings::am1::Def;
-use rustsat::solvers::{Solve, SolverResult};
-use rustsat::types::Var as satVar;
-use sat_rs::conversions::{self, conv_to_clause, conv_to_formula};
-use std::collections::HashMap;
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::str::FromStr;
 
-use rustsat_minisat::core::Minisat;
+use rustsat::encodings::am1::{self, Ladder};
+use rustsat::encodings::card::{self, Totalizer};
+use rustsat::encodings::pb::{self, DynamicPolyWatchdog, GeneralizedTE};
+use rustsat::instances::{BasicVarManager, Cnf, ManageVars};
+use rustsat::solvers::SolverResult;
+use rustsat::types::{Clause, Lit, TernaryVal, Var as satVar};
+use sat_rs::conversions::{self, conv_to_clause};
+use sat_rs::dimacs::write_dimacs;
+use sat_rs::solvers::Solver as SatSolver;
+use std::collections::HashMap;
+use std::sync::mpsc::{self, RecvTimeoutError};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+use std::time::Duration;
 
-use crate::ast::{Expression, Name};
+use crate::ast::{Constant, DecisionVariable, Domain, Expression, Name, SymbolTable};
+use crate::context::Context;
 use crate::metadata::Metadata;
-use crate::solver::{self, SearchStatus, SolveSuccess, SolverCallback, SolverFamily, SolverMutCallback};
+use crate::solver::{SolveSuccess, SolverCallback, SolverFamily, SolverMutCallback};
 use crate::stats::SolverStats;
-use crate::{ast as conjure_ast, model, Model as ConjureModel};
+use crate::{ast as conjure_ast, Model as ConjureModel};
 
-use super::super::model_modifier::NotModifiable;
+use super::super::model_modifier::{ModelModifier, ModificationFailure};
 use super::super::private;
 use super::super::SearchComplete::*;
 use super::super::SearchIncomplete::*;
@@ -28,19 +32,85 @@ use super::super::SearchStatus::*;
 use super::super::SolverAdaptor;
 use super::super::SolverError;
 use super::super::SolverError::*;
-use super::super::SolverError::*;
 
 use rustsat::instances::SatInstance;
 
 use thiserror::Error;
 
+pub use sat_rs::dimacs::{parse_dimacs, Dimacs};
+pub use sat_rs::proof::{check_drat, check_lrat};
+pub use sat_rs::solvers::{ProofFormat, SatBackend};
+
+/// The encoding used by the [SAT] adaptor for sums of booleans (`sum_leq`, `sum_geq` and `sum_eq`).
+///
+/// Whichever encoding is chosen, at-most-one constraints are given the ladder encoding.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PbEncoding {
+    /// The totalizer cardinality encoding. Weighted terms are expanded into repeated literals.
+    Totalizer,
+
+    /// The generalized totalizer pseudo-boolean encoding.
+    #[default]
+    Gte,
+
+    /// The dynamic polynomial watchdog pseudo-boolean encoding.
+    Dpw,
+}
+
+impl PbEncoding {
+    /// All encodings.
+    pub const ALL: [PbEncoding; 3] = [PbEncoding::Totalizer, PbEncoding::Gte, PbEncoding::Dpw];
+}
+
+impl Display for PbEncoding {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            PbEncoding::Totalizer => write!(f, "totalizer"),
+            PbEncoding::Gte => write!(f, "gte"),
+            PbEncoding::Dpw => write!(f, "dpw"),
+        }
+    }
+}
+
+impl FromStr for PbEncoding {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        PbEncoding::ALL
+            .into_iter()
+            .find(|x| x.to_string().eq_ignore_ascii_case(s))
+            .ok_or(format!(
+                "unknown pseudo-boolean encoding `{}`: expected one of totalizer, gte or dpw",
+                s
+            ))
+    }
+}
+
+/// The size of the encodings of the sums in a model.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct PbEncodingStats {
+    /// The number of clauses added by the encodings.
+    pub clauses: u64,
+
+    /// The number of auxiliary variables added by the encodings.
+    pub aux_vars: u64,
+}
+
 /// A [SolverAdaptor] for interacting with the SatSolver generic and the types thereof.
 
 pub struct SAT {
     __non_constructable: private::Internal,
-    model_inst: Option<SatInstance>,
-    var_map: Option<HashMap<i32, satVar>>,
-    solver_inst: Minisat,
+    cnf: Option<Cnf>,
+    var_map: Option<HashMap<Name, satVar>>,
+
+    // the solver and the encoder, shared with the model modifiers given to the callback during
+    // search.
+    search: Option<Arc<Mutex<SatSearch>>>,
+    backend: SatBackend,
+    time_limit: Option<Duration>,
+    pb_encoding: PbEncoding,
+    assumptions: Vec<Expression>,
+    proof: Option<(PathBuf, ProofFormat)>,
 }
 
 impl private::Sealed for SAT {}
@@ -49,61 +119,770 @@ impl Default for SAT {
     fn default() -> Self {
         SAT {
             __non_constructable: private::Internal,
-            model_inst: None,
+            cnf: None,
             var_map: None,
-            solver_inst: Minisat::default(),
+            search: None,
+            backend: SatBackend::default(),
+            time_limit: None,
+            pb_encoding: PbEncoding::default(),
+            assumptions: Vec::new(),
+            proof: None,
         }
     }
 }
 
 impl SAT {
     pub fn new(model: ConjureModel) -> Self {
-        let model_to_use: Option<SatInstance> = Some(SatInstance::new());
+        SAT::default()
+    }
+
+    pub fn add_clause_to_mod(&self, clause_vec: Vec<i32>) -> () {}
+
+    /// Sets the SAT solver used. This must be called before the model is loaded.
+    ///
+    /// The backend must be enabled by its cargo feature, or loading the model will fail.
+    pub fn with_backend(self, backend: SatBackend) -> Self {
+        SAT { backend, ..self }
+    }
+
+    /// Sets the encoding used for sums of booleans. This must be called before the model is
+    /// loaded.
+    pub fn with_pb_encoding(self, pb_encoding: PbEncoding) -> Self {
         SAT {
-            __non_constructable: private::Internal,
-            model_inst: model_to_use,
-            var_map: None,
-            solver_inst: Minisat::default(),
+            pb_encoding,
+            ..self
         }
     }
 
-    pub fn add_clause_to_mod(&self, clause_vec: Vec<i32>) -> () {}
+    /// Solves under the given assumptions: boolean literals that must hold in all solutions found,
+    /// without being added to the model.
+    ///
+    /// During [`solve_mut`](SolverAdaptor::solve_mut), the assumptions can be replaced using
+    /// [`ModelModifier::set_assumptions`], without rebuilding the solver.
+    pub fn with_assumptions(self, assumptions: Vec<Expression>) -> Self {
+        SAT {
+            assumptions,
+            ..self
+        }
+    }
+
+    /// Writes a proof of unsatisfiability to the given file, which is complete once search has
+    /// finished. This must be called before the model is loaded.
+    ///
+    /// Only some backends can write proofs (see [`SatBackend::supports_proofs`]); loading the model
+    /// fails for the others. The proof is of the clauses given by
+    /// [`write_solver_input_file`](SolverAdaptor::write_solver_input_file), and is only a
+    /// certificate if the model has no solutions and no assumptions are used.
+    pub fn with_proof(self, path: impl Into<PathBuf>, format: ProofFormat) -> Self {
+        SAT {
+            proof: Some((path.into(), format)),
+            ..self
+        }
+    }
+
+    /// Enumerates the solutions of the loaded model, calling `on_solution` for each.
+    ///
+    /// Solutions give the values of the decision variables declared in the model, which have
+    /// [`Name::UserName`]s. After each solution, a clause blocking its assignment to these variables
+    /// (and to the boolean variables encoding them, for integers) is added to the solver, and the
+    /// solver is run again. Auxiliary variables are not blocked, so each solution is only found
+    /// once. Search finishes once the solver reports that there are no more solutions, or
+    /// `on_solution` returns `false`.
+    ///
+    /// Blocking clauses are guarded by a selector literal, which is assumed in each run. If the
+    /// model or the assumptions are changed through a [SatModelModifier], the selector is disabled
+    /// and search restarts with a new one, so solutions found before the change can be found
+    /// again.
+    fn search(
+        &mut self,
+        mut on_solution: impl FnMut(HashMap<Name, Constant>, &Arc<Mutex<SatSearch>>) -> bool,
+    ) -> Result<SolveSuccess, SolverError> {
+        #[allow(clippy::expect_used)]
+        let state = self.search.clone().expect("STATE MACHINE ERR");
+        #[allow(clippy::expect_used)]
+        let var_map = self.var_map.as_ref().expect("STATE MACHINE ERR");
+
+        // interrupt the solver from another thread once the time limit is reached.
+        let (finished_tx, finished_rx) = mpsc::channel::<()>();
+
+        let mut selector = {
+            #[allow(clippy::unwrap_used)]
+            let mut guard = state.lock().unwrap();
+            let search = &mut *guard;
+            search.assumptions = search.encoder.encode_assumptions(&self.assumptions)?;
+            search.modified = false;
+
+            if let Some(time_limit) = self.time_limit {
+                let mut interrupt = search.solver.interrupter();
+                thread::spawn(move || {
+                    if let Err(RecvTimeoutError::Timeout) = finished_rx.recv_timeout(time_limit) {
+                        interrupt();
+                    }
+                });
+            }
+
+            search.encoder.new_var()
+        };
+
+        let mut n_solutions: u64 = 0;
+        let status = loop {
+            #[allow(clippy::unwrap_used)]
+            let mut search = state.lock().unwrap();
+
+            let mut assumptions = search.assumptions.clone();
+            assumptions.push(selector.pos_lit());
+            let res = search
+                .solver
+                .solve_assumps(&assumptions)
+                .map_err(|e| Runtime(format!("{:#}", e)))?;
+
+            match res {
+                SolverResult::Sat => {}
+                SolverResult::Unsat if n_solutions == 0 => break Complete(NoSolutions),
+                SolverResult::Unsat => break Complete(HasSolutions),
+
+                // only interrupted by the time limit
+                SolverResult::Interrupted => break Incomplete(Timeout),
+            };
+
+            n_solutions += 1;
+            let solution = get_solution(&*search.solver, var_map)?;
+            let conjure_solution = sat_solution_to_conjure(&solution, &search.encoder.int_vars);
+
+            // the callback may modify the model, which locks the search state.
+            drop(search);
+            if !on_solution(conjure_solution, &state) {
+                break Incomplete(UserTerminated);
+            }
+
+            #[allow(clippy::unwrap_used)]
+            let mut search = state.lock().unwrap();
+
+            if search.modified {
+                // disable the blocking clauses of this run, and restart.
+                search.modified = false;
+                search
+                    .solver
+                    .add_clause([selector.neg_lit()].into_iter().collect())
+                    .map_err(|e| Runtime(format!("{:#}", e)))?;
+                selector = search.encoder.new_var();
+                continue;
+            }
+
+            // block this solution, so that the next run finds a different one.
+            let blocking_lits: Vec<Lit> = solution
+                .iter()
+                .filter(|(name, _)| is_declared(name))
+                .map(|(_, &(var, value))| if value { var.neg_lit() } else { var.pos_lit() })
+                .collect();
+
+            // a model with no declared boolean variables has exactly one solution.
+            if blocking_lits.is_empty() {
+                break Complete(HasSolutions);
+            }
+
+            let blocking_clause: Clause = blocking_lits
+                .into_iter()
+                .chain([selector.neg_lit()])
+                .collect();
+            search
+                .solver
+                .add_clause(blocking_clause)
+                .map_err(|e| Runtime(format!("{:#}", e)))?;
+        };
+        drop(finished_tx);
+
+        #[allow(clippy::unwrap_used)]
+        let mut search = state.lock().unwrap();
+
+        // the last run assumed the selector, so the proof only refutes the selector. Without any
+        // assumptions, the solver derives the empty clause.
+        if self.proof.is_some() && status == Complete(NoSolutions) && search.assumptions.is_empty()
+        {
+            search
+                .solver
+                .solve()
+                .map_err(|e| Runtime(format!("{:#}", e)))?;
+        }
+
+        let stats = SolverStats {
+            satisfiable: Some(n_solutions > 0),
+            solutions_found: Some(n_solutions),
+            timed_out: Some(status == Incomplete(Timeout)),
+            sat_proof_file: self.proof.as_ref().map(|(path, _)| path.clone()),
+            ..get_solver_stats(&*search.solver, search.encoder.pb_stats)
+        };
+
+        Ok(SolveSuccess { stats, status })
+    }
+}
+
+/// The state of a search by the [SAT] adaptor, shared with the [SatModelModifier]s given to the
+/// callback.
+struct SatSearch {
+    solver: Box<dyn SatSolver>,
+    encoder: CnfEncoder,
+    assumptions: Vec<Lit>,
+
+    /// Whether the model or the assumptions have been changed since the last solution.
+    modified: bool,
+}
+
+/// A [`ModelModifier`] for the SAT adaptor, used by [`SAT::solve_mut`](SolverAdaptor::solve_mut).
+///
+/// New constraints are encoded into clauses and added to the running solver straight away, so
+/// the solver keeps what it has learnt so far instead of being rebuilt.
+struct SatModelModifier {
+    search: Arc<Mutex<SatSearch>>,
+}
+
+impl private::Sealed for SatModelModifier {}
+
+impl ModelModifier for SatModelModifier {
+    /// Adds a constraint to the model. As with the constraints of a model rewritten for SAT, this
+    /// must be a clause, a literal, a sum of booleans, or a conjunction of these.
+    fn add_constraint(&self, constraint: Expression) -> Result<(), ModificationFailure> {
+        #[allow(clippy::unwrap_used)]
+        let mut search = self.search.lock().unwrap();
+        let cnf = search
+            .encoder
+            .encode(vec![constraint])
+            .map_err(|e| ModificationFailure::ArgsInvalid(e.into()))?;
+        search
+            .solver
+            .add_cnf(cnf)
+            .map_err(ModificationFailure::Error)?;
+        search.modified = true;
+        Ok(())
+    }
+
+    fn set_assumptions(&self, assumptions: Vec<Expression>) -> Result<(), ModificationFailure> {
+        #[allow(clippy::unwrap_used)]
+        let mut search = self.search.lock().unwrap();
+        search.assumptions = search
+            .encoder
+            .encode_assumptions(&assumptions)
+            .map_err(|e| ModificationFailure::ArgsInvalid(e.into()))?;
+        search.modified = true;
+        Ok(())
+    }
+}
+
+/// Converts a DIMACS CNF into a conjure model, so that it can be solved with the [SAT] adaptor.
+///
+/// Variables named in the comments of the CNF are given those names, and the others are named
+/// after their number.
+pub fn model_from_dimacs(dimacs: &Dimacs, context: Arc<RwLock<Context<'static>>>) -> ConjureModel {
+    let names: HashMap<i32, Name> = (1..=dimacs.n_vars as i32)
+        .map(|var| {
+            let name = match dimacs.var_names.get(&var) {
+                Some(name) => parse_name(name),
+                None => Name::UserName(format!("x{}", var)),
+            };
+            (var, name)
+        })
+        .collect();
+
+    let variables: SymbolTable = names
+        .values()
+        .map(|name| (name.clone(), DecisionVariable::new(Domain::BoolDomain)))
+        .collect();
+
+    let clauses = dimacs
+        .clauses
+        .iter()
+        .map(|clause| {
+            let lits = clause
+                .iter()
+                .map(|&lit| {
+                    let var = Expression::Reference(Metadata::new(), names[&lit.abs()].clone());
+                    if lit > 0 {
+                        var
+                    } else {
+                        Expression::Not(Metadata::new(), Box::new(var))
+                    }
+                })
+                .collect();
+            Expression::Or(Metadata::new(), lits)
+        })
+        .collect();
+
+    ConjureModel::new(
+        variables,
+        Expression::And(Metadata::new(), clauses),
+        context,
+    )
+}
+
+/// Parses a [Name] written by [write_dimacs], using the [Display](std::fmt::Display) of [Name].
+/// Other names are taken to be user names.
+fn parse_name(name: &str) -> Name {
+    if let Some(x) = name
+        .strip_prefix("UserName(")
+        .and_then(|x| x.strip_suffix(')'))
+    {
+        return Name::UserName(x.to_owned());
+    }
+
+    let machine_name = name
+        .strip_prefix("MachineName(")
+        .and_then(|x| x.strip_suffix(')'))
+        .and_then(|x| x.parse::<i32>().ok());
+    match machine_name {
+        Some(i) => Name::MachineName(i),
+        None => Name::UserName(name.to_owned()),
+    }
+}
+
+/// Gets the statistics kept by the SAT solver for this run.
+fn get_solver_stats(solver: &dyn SatSolver, pb_stats: PbEncodingStats) -> SolverStats {
+    let stats = solver.stats();
+    SolverStats {
+        sat_vars: Some(stats.max_var.map_or(0, |x| x.idx() as u64 + 1)),
+        sat_clauses: Some(stats.n_clauses as u64),
+        sat_solves: Some((stats.n_sat + stats.n_unsat + stats.n_terminated) as u64),
+        sat_avg_clause_len: Some(stats.avg_clause_len as f64),
+        solver_solve_time: Some(stats.cpu_solve_time.as_secs_f64()),
+        timed_out: Some(false),
+        sat_pb_clauses: Some(pb_stats.clauses),
+        sat_pb_aux_vars: Some(pb_stats.aux_vars),
+        ..Default::default()
+    }
+}
+
+/// Gets the value of each decision variable in the solver's current solution.
+fn get_solution(
+    solver: &dyn SatSolver,
+    var_map: &HashMap<Name, satVar>,
+) -> Result<HashMap<Name, (satVar, bool)>, SolverError> {
+    let mut solution = HashMap::new();
+    for (name, &var) in var_map {
+        let value = solver
+            .lit_val(var.pos_lit())
+            .map_err(|e| Runtime(format!("{:#}", e)))?;
+
+        // unconstrained variables can take either value.
+        solution.insert(name.clone(), (var, value == TernaryVal::True));
+    }
+    Ok(solution)
+}
+
+/// Converts a solution found by the SAT solver into conjure [Constant]s, giving the value of each
+/// declared decision variable.
+///
+/// The values of integer variables are decoded from the boolean variables representing them.
+fn sat_solution_to_conjure(
+    solution: &HashMap<Name, (satVar, bool)>,
+    int_vars: &HashMap<Name, Vec<i32>>,
+) -> HashMap<Name, Constant> {
+    let mut conjure_solution: HashMap<Name, Constant> = solution
+        .iter()
+        .filter(|(name, _)| matches!(name, Name::UserName(_)))
+        .map(|(name, &(_, value))| (name.clone(), Constant::Bool(value)))
+        .collect();
+
+    for (name, values) in int_vars {
+        if !matches!(name, Name::UserName(_)) {
+            continue;
+        }
+        let value = decode_int(name, values, solution);
+        conjure_solution.insert(name.clone(), Constant::Int(value));
+    }
+
+    conjure_solution
+}
+
+/// Whether the given boolean variable is a decision variable declared in the model, or encodes
+/// one.
+///
+/// Variables introduced while rewriting the model, with [`Name::MachineName`]s, are auxiliary:
+/// their values are not part of solutions.
+fn is_declared(name: &Name) -> bool {
+    match name {
+        Name::UserName(_) => true,
+        Name::RepresentedName(x, _, _) => matches!(**x, Name::UserName(_)),
+        Name::MachineName(_) => false,
+    }
 }
 
-pub fn instantiate_model_from_conjure(
-    conjure_model: ConjureModel,
-) -> Result<SatInstance, SolverError> {
-    let mut inst: SatInstance = SatInstance::new();
+/// Gets the value of an integer variable from the boolean variables representing it.
+///
+/// See the `SAT_Direct`, `SAT_Order` and `SAT_Log` rule sets for the encodings used.
+fn decode_int(name: &Name, values: &[i32], solution: &HashMap<Name, (satVar, bool)>) -> i32 {
+    let mut value = values[0];
+    for (bit, &(_, bit_value)) in solution {
+        let Name::RepresentedName(x, repr, i) = bit else {
+            continue;
+        };
+        if **x != *name || !bit_value {
+            continue;
+        }
+
+        match repr.as_str() {
+            // x#direct_i is true iff x = i
+            "direct" => return *i,
+
+            // x#order_i is true iff x >= i
+            "order" => value = value.max(*i),
+
+            // x#log_i is bit i of x - lb
+            "log" => value += 1 << *i,
+            _ => {}
+        }
+    }
+    value
+}
 
-    for var_name_ref in conjure_model.variables.keys() {
-        let curr_decision_var = conjure_model
+/// Gets the values in the domain of each integer variable, in ascending order, and the integer
+/// variables that are not encoded into boolean variables.
+///
+/// Integer variables are encoded by one of the SAT integer encoding rule sets, but only if they
+/// appear in a constraint. Those that do not, and have more than one value, are given a direct
+/// encoding by the [CnfEncoder]. Variables represented in more than one encoding are not
+/// supported.
+fn get_int_vars(
+    conjure_model: &ConjureModel,
+) -> Result<(HashMap<Name, Vec<i32>>, Vec<Name>), SolverError> {
+    let mut int_vars = HashMap::new();
+    let mut unencoded = Vec::new();
+    for (name, var) in conjure_model.variables.iter() {
+        let Some(mut values) = var.domain.values_i32() else {
+            continue;
+        };
+        values.sort();
+        values.dedup();
+
+        if values.is_empty() {
+            return Err(ModelInvalid(format!(
+                "variable {} has an empty domain",
+                name
+            )));
+        }
+
+        let mut encodings: Vec<&String> = conjure_model
             .variables
-            .get(var_name_ref)
-            .ok_or_else(|| ModelInvalid(format!("variable {:?} not found", var_name_ref)))?;
+            .keys()
+            .filter_map(|x| match x {
+                Name::RepresentedName(y, repr, _) if **y == *name => Some(repr),
+                _ => None,
+            })
+            .collect();
+        encodings.sort();
+        encodings.dedup();
+        if encodings.len() > 1 {
+            return Err(ModelFeatureNotSupported(format!(
+                "variable {} is represented in more than one integer encoding ({}): enable only one of the SAT_Direct, SAT_Order and SAT_Log rule sets",
+                name,
+                encodings.iter().map(|x| x.as_str()).collect::<Vec<_>>().join(", ")
+            )));
+        }
+        if values.len() > 1 && encodings.is_empty() {
+            unencoded.push(name.clone());
+        }
 
-        {
-            // todo: the scope change may be unneeded
-            // check domain, err if bad domain
-            let cdom = &curr_decision_var.domain;
-            if cdom != &conjure_ast::Domain::BoolDomain {
-                return Err(ModelFeatureNotSupported(format!(
-                    "variable {:?}: expected BoolDomain, found: {:?}",
-                    curr_decision_var, curr_decision_var.domain
-                )));
+        int_vars.insert(name.clone(), values);
+    }
+    unencoded.sort();
+    Ok((int_vars, unencoded))
+}
+
+/// The boolean variable that is true iff the integer variable `name` takes the given value, in
+/// the direct encoding.
+fn direct_name(name: &Name, value: i32) -> Name {
+    Name::RepresentedName(Box::new(name.clone()), String::from("direct"), value)
+}
+
+/// Converts conjure constraints in CNF into clauses.
+///
+/// The encoder keeps track of the SAT variable used for each boolean decision variable, so that
+/// constraints can be encoded incrementally, and solutions mapped back to conjure [Name]s.
+pub struct CnfEncoder {
+    variables: SymbolTable,
+
+    // integer variables are not given SAT variables, but are decoded from the boolean variables
+    // representing them.
+    int_vars: HashMap<Name, Vec<i32>>,
+
+    // integer variables that are not encoded by the rules, which are given a direct encoding here.
+    unencoded_ints: Vec<Name>,
+
+    var_indices: HashMap<Name, i32>,
+    index_map: HashMap<i32, satVar>,
+    var_manager: BasicVarManager,
+    pb_encoding: PbEncoding,
+    pb_stats: PbEncodingStats,
+}
+
+impl CnfEncoder {
+    /// Creates an encoder for the decision variables of the given model. Sums of booleans are
+    /// encoded using the given [PbEncoding].
+    ///
+    /// Integer variables that are not encoded by the rules are given boolean variables for a direct
+    /// encoding, constrained by [`int_domain_constraints`](CnfEncoder::int_domain_constraints).
+    pub fn new(conjure_model: &ConjureModel, pb_encoding: PbEncoding) -> Result<Self, SolverError> {
+        let (int_vars, unencoded_ints) = get_int_vars(conjure_model)?;
+
+        let mut variables = conjure_model.variables.clone();
+        for name in &unencoded_ints {
+            for &value in &int_vars[name] {
+                variables.insert(
+                    direct_name(name, value),
+                    DecisionVariable::new(Domain::BoolDomain),
+                );
+            }
+        }
+
+        Ok(CnfEncoder {
+            variables,
+            int_vars,
+            unencoded_ints,
+            var_indices: HashMap::new(),
+            index_map: HashMap::new(),
+            var_manager: BasicVarManager::default(),
+            pb_encoding,
+            pb_stats: PbEncodingStats::default(),
+        })
+    }
+
+    /// Encodes the given constraints into clauses. Each constraint must be a clause, a literal, a
+    /// sum of booleans, or a conjunction of these.
+    ///
+    /// If encoding fails, the state of the encoder is unchanged.
+    pub fn encode(&mut self, constraints: Vec<Expression>) -> Result<Cnf, SolverError> {
+        let mut clauses: Vec<Expression> = Vec::new();
+        let mut sums: Vec<Expression> = Vec::new();
+
+        let mut todo = constraints;
+        todo.reverse();
+        while let Some(constraint) = todo.pop() {
+            match constraint {
+                Expression::And(_, exprs) => todo.extend(exprs.into_iter().rev()),
+                Expression::SumLeq(_, _, _)
+                | Expression::SumGeq(_, _, _)
+                | Expression::SumEq(_, _, _) => sums.push(constraint),
+                _ => clauses.push(constraint),
+            }
+        }
+
+        let mut var_indices = self.var_indices.clone();
+        let vec_cnf = handle_and(Expression::And(Metadata::new(), clauses), &mut var_indices)
+            .map_err(|e| ModelFeatureNotSupported(e.to_string()))?;
+
+        let mut pb_constraints: Vec<PbConstraint> = Vec::new();
+        for sum in sums {
+            pb_constraints.extend(
+                handle_sum(sum, &mut var_indices)
+                    .map_err(|e| ModelFeatureNotSupported(e.to_string()))?,
+            );
+        }
+
+        self.check_names(&var_indices)?;
+
+        let mut inst = SatInstance::new_with_manager(self.var_manager.clone());
+        let mut index_map = self.index_map.clone();
+        for clause in &vec_cnf {
+            conv_to_clause(clause, &mut inst, &mut index_map)
+                .map_err(|e| ModelInvalid(format!("{:#}", e)))?;
+        }
+
+        let mut pb_stats = self.pb_stats;
+        for pb_constraint in pb_constraints {
+            encode_pb(
+                &pb_constraint,
+                self.pb_encoding,
+                &mut inst,
+                &mut index_map,
+                &mut pb_stats,
+            )?;
+        }
+
+        let (cnf, var_manager) = inst.into_cnf();
+        self.var_indices = var_indices;
+        self.index_map = index_map;
+        self.var_manager = var_manager;
+        self.pb_stats = pb_stats;
+        Ok(cnf)
+    }
+
+    /// Gets the constraints of the direct encoding of each integer variable that is not encoded by
+    /// the rules: at least one, and at most one, of its boolean variables is true. These are to be
+    /// encoded with the constraints of the model.
+    pub fn int_domain_constraints(&self) -> Vec<Expression> {
+        self.unencoded_ints
+            .iter()
+            .flat_map(|name| {
+                let lits: Vec<Expression> = self.int_vars[name]
+                    .iter()
+                    .map(|&value| Expression::Reference(Metadata::new(), direct_name(name, value)))
+                    .collect();
+                [
+                    Expression::Or(Metadata::new(), lits.clone()),
+                    Expression::SumLeq(
+                        Metadata::new(),
+                        lits,
+                        Box::new(Expression::Constant(Metadata::new(), Constant::Int(1))),
+                    ),
+                ]
+            })
+            .collect()
+    }
+
+    /// Converts the given boolean literals into SAT literals, for use as assumptions.
+    ///
+    /// If this fails, the state of the encoder is unchanged.
+    pub fn encode_assumptions(
+        &mut self,
+        assumptions: &[Expression],
+    ) -> Result<Vec<Lit>, SolverError> {
+        let mut var_indices = self.var_indices.clone();
+        let lits = assumptions
+            .iter()
+            .map(|x| handle_lit(x.clone(), &mut var_indices))
+            .collect::<Result<Vec<i32>, _>>()
+            .map_err(|e| ModelFeatureNotSupported(e.to_string()))?;
+
+        self.check_names(&var_indices)?;
+
+        let mut inst = SatInstance::new_with_manager(self.var_manager.clone());
+        let mut index_map = self.index_map.clone();
+        let lits = lits
+            .into_iter()
+            .map(|x| conversions::mk_lit(x, &mut inst, &mut index_map))
+            .collect::<Result<Vec<Lit>, _>>()
+            .map_err(|e| ModelInvalid(format!("{:#}", e)))?;
+
+        self.var_indices = var_indices;
+        self.index_map = index_map;
+        self.var_manager = inst.into_cnf().1;
+        Ok(lits)
+    }
+
+    /// Gets the SAT variable used for each boolean decision variable.
+    ///
+    /// Variables that appear in no clause are given a SAT variable too, so that they are given a
+    /// value in each solution.
+    pub fn decision_vars(&mut self) -> HashMap<Name, satVar> {
+        let mut var_map: HashMap<Name, satVar> = HashMap::new();
+        for name in self.variables.keys() {
+            if self.int_vars.contains_key(name) {
+                continue;
             }
+
+            let index = get_namevar_as_int(name.clone(), &mut self.var_indices);
+            let var = *self
+                .index_map
+                .entry(index)
+                .or_insert_with(|| self.var_manager.new_var());
+            var_map.insert(name.clone(), var);
+        }
+        var_map
+    }
+
+    /// Gets a new SAT variable, which is not used by any constraint.
+    pub fn new_var(&mut self) -> satVar {
+        self.var_manager.new_var()
+    }
+
+    /// Checks that the given variables are boolean decision variables of the model.
+    fn check_names(&self, var_indices: &HashMap<Name, i32>) -> Result<(), SolverError> {
+        if let Some(name) = var_indices.keys().find(|x| !self.variables.contains_key(x)) {
+            return Err(ModelInvalid(
+                CNFError::VariableNameNotFound(name.clone()).to_string(),
+            ));
+        }
+
+        if let Some(name) = var_indices.keys().find(|x| self.int_vars.contains_key(x)) {
+            return Err(ModelInvalid(
+                CNFError::BadVariableType(name.clone()).to_string(),
+            ));
         }
+
+        Ok(())
+    }
+}
+
+/// A pseudo-boolean constraint `sum(weight * lit) <= ub`, over CNF literals.
+///
+/// Each literal appears once, with its weight.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PbConstraint {
+    pub lits: Vec<(i32, usize)>,
+    pub ub: i64,
+}
+
+/// Encodes a pseudo-boolean constraint into clauses, adding them to the SAT instance.
+fn encode_pb(
+    pb_constraint: &PbConstraint,
+    pb_encoding: PbEncoding,
+    inst: &mut SatInstance,
+    index_map: &mut HashMap<i32, satVar>,
+    pb_stats: &mut PbEncodingStats,
+) -> Result<(), SolverError> {
+    let weighted_lits = pb_constraint
+        .lits
+        .iter()
+        .map(|&(x, weight)| conversions::mk_lit(x, inst, index_map).map(|lit| (lit, weight)))
+        .collect::<Result<Vec<(Lit, usize)>, _>>()
+        .map_err(|e| ModelInvalid(format!("{:#}", e)))?;
+
+    // the sum is at least 0, and at most the sum of the weights.
+    if pb_constraint.ub < 0 {
+        inst.add_clause(Clause::new());
+        pb_stats.clauses += 1;
+        return Ok(());
+    }
+    let total_weight: usize = weighted_lits.iter().map(|&(_, weight)| weight).sum();
+    if pb_constraint.ub >= total_weight as i64 {
+        return Ok(());
     }
+    let ub = pb_constraint.ub as usize;
 
-    let md = Metadata {
-        clean: false,
-        etype: None,
+    let n_vars_before = inst.n_vars();
+    let mut cnf = Cnf::new();
+
+    let units = match pb_encoding {
+        _ if ub == 1 && weighted_lits.iter().all(|&(_, weight)| weight == 1) => {
+            let mut enc = Ladder::from_iter(weighted_lits.into_iter().map(|(lit, _)| lit));
+            am1::Encode::encode(&mut enc, &mut cnf, inst.var_manager_mut())
+                .map_err(|e| Runtime(format!("{:?}", e)))?;
+            vec![]
+        }
+        PbEncoding::Totalizer => {
+            // a cardinality encoding, so weighted literals are repeated.
+            let lits = weighted_lits
+                .into_iter()
+                .flat_map(|(lit, weight)| std::iter::repeat(lit).take(weight));
+            let mut enc = Totalizer::from_iter(lits);
+            card::BoundUpper::encode_ub(&mut enc, ub..=ub, &mut cnf, inst.var_manager_mut())
+                .map_err(|e| Runtime(format!("{:?}", e)))?;
+            card::BoundUpper::enforce_ub(&enc, ub).map_err(|e| Runtime(format!("{:?}", e)))?
+        }
+        PbEncoding::Gte | PbEncoding::Dpw => {
+            if pb_encoding == PbEncoding::Gte {
+                let mut enc = GeneralizedTE::from_iter(weighted_lits);
+                pb::BoundUpper::encode_ub(&mut enc, ub..=ub, &mut cnf, inst.var_manager_mut())
+                    .map_err(|e| Runtime(format!("{:?}", e)))?;
+                pb::BoundUpper::enforce_ub(&enc, ub).map_err(|e| Runtime(format!("{:?}", e)))?
+            } else {
+                let mut enc = DynamicPolyWatchdog::from_iter(weighted_lits);
+                pb::BoundUpper::encode_ub(&mut enc, ub..=ub, &mut cnf, inst.var_manager_mut())
+                    .map_err(|e| Runtime(format!("{:?}", e)))?;
+                pb::BoundUpper::enforce_ub(&enc, ub).map_err(|e| Runtime(format!("{:?}", e)))?
+            }
+        }
     };
 
-    let constraints_vec: Vec<Expression> = conjure_model.get_constraints_vec();
-    let vec_cnf = handle_and(Expression::And(md, constraints_vec));
-    conv_to_formula(&(vec_cnf.unwrap()), &mut inst);
+    for lit in units {
+        cnf.add_unit(lit);
+    }
+
+    pb_stats.clauses += cnf.len() as u64;
+    pb_stats.aux_vars += (inst.n_vars() - n_vars_before) as u64;
+    for clause in cnf {
+        inst.add_clause(clause);
+    }
 
-    Ok(inst)
+    Ok(())
 }
 
 impl SolverAdaptor for SAT {
@@ -112,105 +891,153 @@ impl SolverAdaptor for SAT {
         callback: SolverCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
-        // solver = self.solver
-        // handle
-        let cnf_func: rustsat::instances::Cnf = self.model_inst.clone().unwrap().into_cnf().0;
-        &self.solver_inst.add_cnf(cnf_func);
-        let res = &self.solver_inst.solve().unwrap();
-
-        let solver_res = match res {
-            SolverResult::Sat => true,
-            SolverResult::Unsat => false,
-
-            // should not arise:
-            SolverResult::Interrupted => Err(SolverError::Runtime(format!("SatInstance may be invalid, Interrupted.")))?,
-        };
-
-        // error thrown always. impermanent
-        // will eventually have a SolveSucess instance being returned with Ok(), when the implementation is more permanent.
-        // Ok(SolveSuccess{
-        //     // stats tbd, fails
-        //     stats:SolverStats::with_timings(10.0),
-        //     status: SearchStatus::Complete(())
-        // });
-        print!("{}", solver_res);
-
-        Err(OpNotImplemented("solve_mut".to_owned()))
-        // Ok(())
+        self.search(|solution, _| callback(solution))
     }
 
+    /// Solves incrementally, allowing the model and the assumptions to be changed in the callback.
+    ///
+    /// Changes made through the [`ModelModifier`] are applied to the running solver, and search
+    /// restarts once the callback returns. Search finishes once a run finds no more solutions, or
+    /// the callback returns `false`.
+    ///
+    /// The time limit, if any, applies to the search as a whole.
     fn solve_mut(
         &mut self,
         callback: SolverMutCallback,
         _: private::Internal,
     ) -> Result<SolveSuccess, SolverError> {
-        Err(OpNotSupported("solve_mut".to_owned()))
+        self.search(|solution, search| {
+            let modifier = SatModelModifier {
+                search: search.clone(),
+            };
+            callback(solution, Box::new(modifier))
+        })
     }
 
     fn load_model(&mut self, model: ConjureModel, _: private::Internal) -> Result<(), SolverError> {
-        let inst_res = instantiate_model_from_conjure(model);
-        self.model_inst = Some(inst_res.unwrap());
+        let mut encoder = CnfEncoder::new(&model, self.pb_encoding)?;
+        let mut constraints = model.get_constraints_vec();
+        constraints.extend(encoder.int_domain_constraints());
+        let cnf = encoder.encode(constraints)?;
+        let var_map = encoder.decision_vars();
+
+        let mut solver = self
+            .backend
+            .new_solver()
+            .map_err(|e| ModelFeatureNotSupported(format!("{:#}", e)))?;
+        if let Some((path, format)) = &self.proof {
+            solver
+                .trace_proof(path, *format)
+                .map_err(|e| ModelFeatureNotSupported(format!("{:#}", e)))?;
+        }
+        solver
+            .add_cnf(cnf.clone())
+            .map_err(|e| Runtime(format!("{:#}", e)))?;
+
+        self.cnf = Some(cnf);
+        self.var_map = Some(var_map);
+        self.search = Some(Arc::new(Mutex::new(SatSearch {
+            solver,
+            encoder,
+            assumptions: Vec::new(),
+            modified: false,
+        })));
         Ok(())
     }
 
+    fn set_time_limit(&mut self, time_limit: Duration, _: private::Internal) {
+        self.time_limit = Some(time_limit);
+    }
+
     fn get_family(&self) -> SolverFamily {
         SolverFamily::SAT
     }
+
+    fn get_name(&self) -> Option<String> {
+        Some(format!("SAT ({})", self.backend))
+    }
+
+    /// Writes the clauses given to the SAT solver as a DIMACS CNF, with comments giving the
+    /// [Name] of each decision variable.
+    fn write_solver_input_file(
+        &self,
+        writer: &mut impl std::io::Write,
+    ) -> Result<(), std::io::Error> {
+        #[allow(clippy::expect_used)]
+        let cnf = self.cnf.as_ref().expect("STATE MACHINE ERR");
+        #[allow(clippy::expect_used)]
+        let search = self.search.as_ref().expect("STATE MACHINE ERR");
+        #[allow(clippy::expect_used)]
+        let var_map = self.var_map.as_ref().expect("STATE MACHINE ERR");
+
+        #[allow(clippy::unwrap_used)]
+        let n_vars = search.lock().unwrap().encoder.var_manager.n_used();
+        let dimacs = Dimacs {
+            n_vars,
+            clauses: cnf
+                .iter()
+                .map(|clause| clause.iter().map(|lit| lit.to_ipasir()).collect())
+                .collect(),
+            var_names: var_map
+                .iter()
+                .map(|(name, var)| (var.idx() as i32 + 1, name.to_string()))
+                .collect(),
+        };
+
+        write_dimacs(writer, &dimacs)
+    }
 }
 
-pub fn handle_expr(e: Expression) -> Result<(Vec<Vec<i32>>), CNFError> {
+pub fn handle_expr(
+    e: Expression,
+    var_indices: &mut HashMap<Name, i32>,
+) -> Result<Vec<Vec<i32>>, CNFError> {
     match e {
-        Expression::And(_, _) => Ok(handle_and(e).unwrap()),
+        Expression::And(_, _) => handle_and(e, var_indices),
         _ => Err(CNFError::UnexpectedExpression(e)),
     }
 }
 
-pub fn get_namevar_as_int(name: Name) -> Result<i32, CNFError> {
-    match name {
-        Name::MachineName(val) => Ok(val),
-        _ => Err(CNFError::BadVariableType(name)),
-    }
+/// Gets the index of the given variable in the CNF, giving it the next free index if it does not
+/// have one yet.
+///
+/// Indices start at 1, as negative numbers are used for negated literals.
+pub fn get_namevar_as_int(name: Name, var_indices: &mut HashMap<Name, i32>) -> i32 {
+    let next_index = var_indices.len() as i32 + 1;
+    *var_indices.entry(name).or_insert(next_index)
 }
 
-pub fn handle_lit(e: Expression) -> Result<i32, CNFError> {
+pub fn handle_lit(e: Expression, var_indices: &mut HashMap<Name, i32>) -> Result<i32, CNFError> {
     match e {
         Expression::Not(_, heap_expr) => {
             let expr = *heap_expr;
             match expr {
                 Expression::Nothing => todo!(), // panic?
-                Expression::Not(_md, e) => handle_lit(*e),
-                // todo(ss504): decide
-                Expression::Reference(_md, name) => {
-                    let check = get_namevar_as_int(name).unwrap();
-                    match check == 0 {
-                        true => Ok(1),
-                        false => Ok(0),
-                    }
-                }
+                Expression::Not(_md, e) => handle_lit(*e, var_indices),
+                Expression::Reference(_md, name) => Ok(-get_namevar_as_int(name, var_indices)),
                 _ => Err(CNFError::UnexpectedExpressionInsideNot(expr)),
             }
         }
-        Expression::Reference(_md, name) => get_namevar_as_int(name),
+        Expression::Reference(_md, name) => Ok(get_namevar_as_int(name, var_indices)),
         _ => Err(CNFError::UnexpectedLiteralExpression(e)),
     }
 }
 
-pub fn handle_or(e: Expression) -> Result<(Vec<i32>), CNFError> {
+pub fn handle_or(
+    e: Expression,
+    var_indices: &mut HashMap<Name, i32>,
+) -> Result<Vec<i32>, CNFError> {
     let vec_clause = match e {
         Expression::Or(_md, vec) => vec,
         _ => Err(CNFError::UnexpectedExpression(e))?,
     };
 
-    // if vec_clause.len() != 2 {
-    //     panic!("Villain, What hast thou done?\nThat which thou canst not undo.")
-    // };
-
     let mut ret_clause: Vec<i32> = Vec::new();
 
     for expr in vec_clause {
         match expr {
-            Expression::Reference(_, _) => ret_clause.push(handle_lit(expr).unwrap()),
-            Expression::Not(_, _) => ret_clause.push(handle_lit(expr).unwrap()),
+            Expression::Reference(_, _) => ret_clause.push(handle_lit(expr, var_indices)?),
+            Expression::Not(_, _) => ret_clause.push(handle_lit(expr, var_indices)?),
             _ => Err(CNFError::UnexpectedExpressionInsideOr(expr))?,
         }
     }
@@ -218,18 +1045,86 @@ pub fn handle_or(e: Expression) -> Result<(Vec<i32>), CNFError> {
     Ok(ret_clause)
 }
 
-pub fn handle_and(e: Expression) -> Result<(Vec<Vec<i32>>), CNFError> {
+/// Converts a sum of boolean literals and constants into pseudo-boolean constraints of the form
+/// `sum(lits) <= ub`.
+///
+/// A literal that appears more than once is given a single weighted term. A `sum_geq` is given as
+/// a `sum_leq` over the negated literals, as `sum(w * lits) >= k` is equivalent to
+/// `sum(w * not lits) <= sum(w) - k`. A `sum_eq` gives one constraint of each kind.
+pub fn handle_sum(
+    e: Expression,
+    var_indices: &mut HashMap<Name, i32>,
+) -> Result<Vec<PbConstraint>, CNFError> {
+    let (exprs, k, is_leq, is_geq) = match &e {
+        Expression::SumLeq(_, exprs, k) => (exprs, k, true, false),
+        Expression::SumGeq(_, exprs, k) => (exprs, k, false, true),
+        Expression::SumEq(_, exprs, k) => (exprs, k, true, true),
+        _ => Err(CNFError::UnexpectedExpression(e.clone()))?,
+    };
+
+    let Expression::Constant(_, Constant::Int(k)) = k.as_ref() else {
+        Err(CNFError::UnexpectedExpression(e.clone()))?
+    };
+
+    // constant terms are moved to the right hand side, and repeated literals become weights.
+    let mut lits: Vec<(i32, usize)> = Vec::new();
+    let mut offset: i64 = 0;
+    for expr in exprs {
+        match expr {
+            Expression::Constant(_, Constant::Int(x)) => offset += *x as i64,
+            Expression::Constant(_, Constant::Bool(x)) => offset += *x as i64,
+            _ => {
+                let lit = handle_lit(expr.clone(), var_indices)?;
+                match lits.iter_mut().find(|(x, _)| *x == lit) {
+                    Some((_, weight)) => *weight += 1,
+                    None => lits.push((lit, 1)),
+                }
+            }
+        }
+    }
+    let k = *k as i64 - offset;
+    let total_weight: usize = lits.iter().map(|&(_, weight)| weight).sum();
+
+    let mut pb_constraints = Vec::new();
+    if is_leq {
+        pb_constraints.push(PbConstraint {
+            lits: lits.clone(),
+            ub: k,
+        });
+    }
+    if is_geq {
+        pb_constraints.push(PbConstraint {
+            ub: total_weight as i64 - k,
+            lits: lits.into_iter().map(|(x, weight)| (-x, weight)).collect(),
+        });
+    }
+
+    Ok(pb_constraints)
+}
+
+pub fn handle_and(
+    e: Expression,
+    var_indices: &mut HashMap<Name, i32>,
+) -> Result<Vec<Vec<i32>>, CNFError> {
     let vec_cnf = match e {
         Expression::And(_md, vec_and) => vec_and,
-        _ => panic!("Villain, What hast thou done?\nThat which thou canst not undo."),
+        _ => Err(CNFError::UnexpectedExpression(e))?,
     };
 
     let mut ret_vec_of_vecs: Vec<Vec<i32>> = Vec::new();
 
     for expr in vec_cnf {
         match expr {
-            Expression::Or(_, _) => ret_vec_of_vecs.push(handle_or(expr).unwrap()),
-            _ => Err(CNFError::UnexpectedExpressionInsideOr(expr))?,
+            Expression::Or(_, _) => ret_vec_of_vecs.push(handle_or(expr, var_indices)?),
+
+            // single literals are unit clauses
+            Expression::Reference(_, _) | Expression::Not(_, _) => {
+                ret_vec_of_vecs.push(vec![handle_lit(expr, var_indices)?])
+            }
+
+            Expression::Constant(_, Constant::Bool(true)) => {}
+            Expression::Constant(_, Constant::Bool(false)) => ret_vec_of_vecs.push(vec![]),
+            _ => Err(CNFError::UnexpectedExpressionInsideAnd(expr))?,
         }
     }
 
@@ -250,7 +1145,7 @@ pub enum CNFError {
     #[error("Unexpected Expression `{0}` as literal. Only Not() or Reference() allowed!")]
     UnexpectedLiteralExpression(Expression),
 
-    #[error("Unexpected Expression `{0}` inside And(). Only And(vec<Or>) allowed!")]
+    #[error("Unexpected Expression `{0}` inside And(). Only clauses and literals allowed!")]
     UnexpectedExpressionInsideAnd(Expression),
 
     #[error("Unexpected Expression `{0}` inside Or(). Only Or(lit, lit) allowed!")]
@@ -262,7 +1157,7 @@ pub enum CNFError {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::ast::{Expression, Name};
+    use crate::ast::{Constant, Expression, Name};
     use crate::metadata::Metadata;
     use crate::solver::{self, SearchStatus, SolveSuccess, SolverCallback, SolverFamily, SolverMutCallback};
     use crate::stats::SolverStats;
@@ -272,21 +1167,21 @@ mod tests {
     #[test]
     fn test_handle_expr_unexpected_expression() {
         let expr = Expression::Not(Metadata::new(), Box::new(Expression::Reference(Metadata::new(), Name::MachineName(1))));
-        let result = handle_expr(expr);
+        let result = handle_expr(expr, &mut HashMap::new());
         assert!(matches!(result, Err(CNFError::UnexpectedExpression(_))));
     }
 
     #[test]
     fn test_handle_lit_unexpected_expression_inside_not() {
         let expr = Expression::Not(Metadata::new(), Box::new(Expression::And(Metadata::new(), vec![])));
-        let result = handle_lit(expr);
+        let result = handle_lit(expr, &mut HashMap::new());
         assert!(matches!(result, Err(CNFError::UnexpectedExpressionInsideNot(_))));
     }
 
     #[test]
     fn test_handle_lit_unexpected_literal_expression() {
         let expr = Expression::And(Metadata::new(), vec![]);
-        let result = handle_lit(expr);
+        let result = handle_lit(expr, &mut HashMap::new());
         assert!(matches!(result, Err(CNFError::UnexpectedLiteralExpression(_))));
     }
 
@@ -299,7 +1194,7 @@ mod tests {
                 Expression::And(Metadata::new(), vec![]),
             ],
         );
-        let result = handle_or(expr);
+        let result = handle_or(expr, &mut HashMap::new());
         assert!(matches!(result, Err(CNFError::UnexpectedExpressionInsideOr(_))));
     }
 
@@ -314,7 +1209,7 @@ mod tests {
                 ]),
             ],
         );
-        let result = handle_expr(expr);
+        let result = handle_expr(expr, &mut HashMap::new());
         assert!(result.is_ok());
         let cnf_result = result.unwrap();
         assert_eq!(cnf_result.len(), 1); // Check that we have one clause
@@ -332,7 +1227,7 @@ mod tests {
                 ]),
             ],
         );
-        let result = handle_expr(expr);
+        let result = handle_expr(expr, &mut HashMap::new());
         assert!(result.is_ok());
         let cnf_result = result.unwrap();
         // Check number of clauses
@@ -342,8 +1237,8 @@ mod tests {
         assert_eq!(cnf_result[0].len(), 2); 
 
         // check literals
-        assert_eq!(cnf_result[0][0], 0);
-        assert_eq!(cnf_result[0][1], 0);
+        assert_eq!(cnf_result[0][0], 1);
+        assert_eq!(cnf_result[0][1], 1);
     }
 
     #[test]
@@ -355,9 +1250,84 @@ mod tests {
             )
         );
 
-        let result = handle_lit(expr);
+        let result = handle_lit(expr, &mut HashMap::new());
         assert!(result.is_ok());
         let lit_result = result.unwrap();
-        assert_eq!(lit_result, 1); // Check that we have one clause
+        assert_eq!(lit_result, -1); // Check that the literal is negative
+    }
+
+    #[test]
+    fn test_handle_and_user_names() {
+        let a = Name::UserName(String::from("a"));
+        let b = Name::UserName(String::from("b"));
+        let expr = Expression::And(
+            Metadata::new(),
+            vec![
+                Expression::Or(
+                    Metadata::new(),
+                    vec![
+                        Expression::Reference(Metadata::new(), a.clone()),
+                        Expression::Not(
+                            Metadata::new(),
+                            Box::new(Expression::Reference(Metadata::new(), b.clone())),
+                        ),
+                    ],
+                ),
+                Expression::Reference(Metadata::new(), b.clone()),
+            ],
+        );
+
+        let mut var_indices = HashMap::new();
+        let result = handle_and(expr, &mut var_indices);
+        assert_eq!(result.unwrap(), vec![vec![1, -2], vec![2]]);
+        assert_eq!(var_indices[&a], 1);
+        assert_eq!(var_indices[&b], 2);
+    }
+
+    #[test]
+    fn test_handle_sum_weights() {
+        // a + a + b + 1 >= 3, which is 2a + b >= 2.
+        let a = Expression::Reference(Metadata::new(), Name::UserName(String::from("a")));
+        let b = Expression::Reference(Metadata::new(), Name::UserName(String::from("b")));
+        let expr = Expression::SumGeq(
+            Metadata::new(),
+            vec![
+                a.clone(),
+                a,
+                b,
+                Expression::Constant(Metadata::new(), Constant::Int(1)),
+            ],
+            Box::new(Expression::Constant(Metadata::new(), Constant::Int(3))),
+        );
+
+        let result = handle_sum(expr, &mut HashMap::new());
+        assert_eq!(
+            result.unwrap(),
+            vec![PbConstraint {
+                lits: vec![(-1, 2), (-2, 1)],
+                ub: 1,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_write_solver_input_file_after_solve() {
+        let a = Name::UserName(String::from("a"));
+        let mut model = ConjureModel::new(
+            HashMap::new(),
+            Expression::Reference(Metadata::new(), a.clone()),
+            Default::default(),
+        );
+        model
+            .variables
+            .insert(a, DecisionVariable::new(Domain::BoolDomain));
+
+        let mut sat = SAT::default();
+        sat.load_model(model, private::Internal).unwrap();
+        sat.solve(Box::new(|_| true), private::Internal).unwrap();
+
+        let mut dimacs: Vec<u8> = vec![];
+        sat.write_solver_input_file(&mut dimacs).unwrap();
+        assert!(!dimacs.is_empty());
     }
 }
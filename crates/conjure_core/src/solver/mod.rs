@@ -98,8 +98,9 @@ use std::collections::HashMap;
 use std::error::Error;
 use std::fmt::{Debug, Display};
 use std::rc::Rc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 use std::sync::{Arc, RwLock};
-use std::time::Instant;
+use std::time::{Duration, Instant};
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
@@ -113,6 +114,7 @@ use crate::Model;
 
 use self::model_modifier::ModelModifier;
 use self::states::*;
+use self::validation::SolutionValidator;
 
 pub mod adaptors;
 pub mod model_modifier;
@@ -121,6 +123,7 @@ pub mod model_modifier;
 mod private;
 
 pub mod states;
+pub mod validation;
 
 #[derive(
     Debug,
@@ -139,6 +142,10 @@ pub mod states;
 pub enum SolverFamily {
     SAT,
     Minion,
+
+    /// Solving by trying every assignment, using [`adaptors::BruteForce`]. No rules are specific
+    /// to this family, so models are solved as they are.
+    BruteForce,
 }
 
 /// The type for user-defined callbacks for use with [Solver].
@@ -222,6 +229,14 @@ pub trait SolverAdaptor: private::Sealed + Any {
     fn load_model(&mut self, model: Model, _: private::Internal) -> Result<(), SolverError>;
     fn init_solver(&mut self, _: private::Internal) {}
 
+    /// Sets a wall-clock time limit for [`solve`](SolverAdaptor::solve) and
+    /// [`solve_mut`](SolverAdaptor::solve_mut).
+    ///
+    /// Implementations **must** stop search once the time limit is reached, and return a
+    /// [`SolveSuccess`] with the status [`SearchIncomplete::Timeout`]. Solutions found before the
+    /// time limit are given to the user callback as usual.
+    fn set_time_limit(&mut self, time_limit: Duration, _: private::Internal);
+
     /// Get the solver family that this solver adaptor belongs to
     fn get_family(&self) -> SolverFamily;
 
@@ -238,6 +253,21 @@ pub trait SolverAdaptor: private::Sealed + Any {
             ..stats
         }
     }
+
+    /// Writes the loaded model to the given writer in the underlying solver's input format.
+    ///
+    /// This is intended for debugging, and for reproducing runs with standalone versions of the
+    /// solver. Adaptors for solvers without a textual input format **should** return an error of
+    /// kind [`Unsupported`](std::io::ErrorKind::Unsupported).
+    fn write_solver_input_file(
+        &self,
+        writer: &mut impl std::io::Write,
+    ) -> Result<(), std::io::Error> {
+        Err(std::io::Error::new(
+            std::io::ErrorKind::Unsupported,
+            "writing solver input files is not supported by this solver adaptor",
+        ))
+    }
 }
 
 /// An abstract representation of a constraints solver.
@@ -258,6 +288,25 @@ pub struct Solver<A: SolverAdaptor, State: SolverState = Init> {
     state: State,
     adaptor: A,
     context: Option<Arc<RwLock<Context<'static>>>>,
+    solve_options: SolveOptions,
+    validator: Option<Arc<SolutionValidator>>,
+}
+
+/// Options for solving that are supported by all solver adaptors.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SolveOptions {
+    /// Stop search after this many solutions, with the status
+    /// [`SearchIncomplete::SolutionLimitReached`].
+    ///
+    /// If `None`, all solutions are found.
+    pub max_solutions: Option<u64>,
+
+    /// Stop search after this amount of wall-clock time, with the status
+    /// [`SearchIncomplete::Timeout`].
+    ///
+    /// Some solvers can only stop after a whole number of seconds, so may run for up to a second
+    /// longer than this. In particular, Minion rounds the time limit up to the next second.
+    pub time_limit: Option<Duration>,
 }
 
 impl<Adaptor: SolverAdaptor> Solver<Adaptor> {
@@ -266,12 +315,45 @@ impl<Adaptor: SolverAdaptor> Solver<Adaptor> {
             state: Init,
             adaptor: solver_adaptor,
             context: None,
+            solve_options: SolveOptions::default(),
+            validator: None,
         };
 
         solver.adaptor.init_solver(private::Internal);
         solver
     }
 
+    /// Stops search after the given amount of wall-clock time.
+    ///
+    /// If the time limit is reached, solving returns successfully with the status
+    /// [`SearchIncomplete::Timeout`], having called the callback on all solutions found so far.
+    /// See [`SolveOptions::time_limit`] for how precise this is.
+    pub fn with_time_limit(mut self, time_limit: Duration) -> Solver<Adaptor> {
+        self.adaptor.set_time_limit(time_limit, private::Internal);
+        self.solve_options.time_limit = Some(time_limit);
+        self
+    }
+
+    /// Sets the given [`SolveOptions`], replacing any previously set.
+    pub fn with_solve_options(mut self, solve_options: SolveOptions) -> Solver<Adaptor> {
+        if let Some(time_limit) = solve_options.time_limit {
+            self.adaptor.set_time_limit(time_limit, private::Internal);
+        }
+        self.solve_options = solve_options;
+        self
+    }
+
+    /// Checks each solution found against the given model, usually the model as parsed before
+    /// rewriting.
+    ///
+    /// Search stops at the first solution that is invalid, which is not given to the callback, and
+    /// solving fails with [`SolverError::InvalidSolution`] naming the violated constraint. See
+    /// [`validation::validate_solution`].
+    pub fn with_validation(mut self, original_model: &Model) -> Solver<Adaptor> {
+        self.validator = Some(Arc::new(SolutionValidator::new(original_model)));
+        self
+    }
+
     pub fn get_family(&self) -> SolverFamily {
         self.adaptor.get_family()
     }
@@ -284,6 +366,8 @@ impl<A: SolverAdaptor> Solver<A, Init> {
             state: ModelLoaded,
             adaptor: self.adaptor,
             context: Some(model.context.clone()),
+            solve_options: self.solve_options,
+            validator: self.validator,
         })
     }
 }
@@ -296,8 +380,21 @@ impl<A: SolverAdaptor> Solver<A, ModelLoaded> {
         #[allow(clippy::unwrap_used)]
         let start_time = Instant::now();
 
+        let limit = Arc::new(SolutionLimit::new(self.solve_options.max_solutions));
+        let limit_2 = limit.clone();
+        let validator = self.validator.clone();
+        let callback: SolverCallback = Box::new(move |solution| {
+            limit_2.check(|| match &validator {
+                Some(validator) => validator.check(&solution, || callback(solution.clone())),
+                None => callback(solution),
+            })
+        });
+
         #[allow(clippy::unwrap_used)]
         let result = self.adaptor.solve(callback, private::Internal);
+        if let Some(e) = self.validator.as_ref().and_then(|x| x.error()) {
+            return Err(e);
+        }
 
         let duration = start_time.elapsed();
 
@@ -312,10 +409,12 @@ impl<A: SolverAdaptor> Solver<A, ModelLoaded> {
                     adaptor: self.adaptor,
                     state: ExecutionSuccess {
                         stats,
-                        status: x.status,
+                        status: limit.adjust_status(x.status),
                         _sealed: private::Internal,
                     },
                     context: self.context,
+                    solve_options: self.solve_options,
+                    validator: self.validator,
                 })
             }
             Err(x) => Err(x),
@@ -329,8 +428,23 @@ impl<A: SolverAdaptor> Solver<A, ModelLoaded> {
         #[allow(clippy::unwrap_used)]
         let start_time = Instant::now();
 
+        let limit = Arc::new(SolutionLimit::new(self.solve_options.max_solutions));
+        let limit_2 = limit.clone();
+        let validator = self.validator.clone();
+        let callback: SolverMutCallback = Box::new(move |solution, modifier| {
+            limit_2.check(|| match &validator {
+                Some(validator) => {
+                    validator.check(&solution, || callback(solution.clone(), modifier))
+                }
+                None => callback(solution, modifier),
+            })
+        });
+
         #[allow(clippy::unwrap_used)]
         let result = self.adaptor.solve_mut(callback, private::Internal);
+        if let Some(e) = self.validator.as_ref().and_then(|x| x.error()) {
+            return Err(e);
+        }
 
         let duration = start_time.elapsed();
 
@@ -345,10 +459,12 @@ impl<A: SolverAdaptor> Solver<A, ModelLoaded> {
                     adaptor: self.adaptor,
                     state: ExecutionSuccess {
                         stats,
-                        status: x.status,
+                        status: limit.adjust_status(x.status),
                         _sealed: private::Internal,
                     },
                     context: self.context,
+                    solve_options: self.solve_options,
+                    validator: self.validator,
                 })
             }
             Err(x) => Err(x),
@@ -356,11 +472,28 @@ impl<A: SolverAdaptor> Solver<A, ModelLoaded> {
     }
 }
 
+impl<A: SolverAdaptor> Solver<A, ModelLoaded> {
+    /// Writes the loaded model to the given writer in the underlying solver's input format.
+    ///
+    /// See [`SolverAdaptor::write_solver_input_file`].
+    pub fn write_solver_input_file(
+        &self,
+        writer: &mut impl std::io::Write,
+    ) -> Result<(), std::io::Error> {
+        self.adaptor.write_solver_input_file(writer)
+    }
+}
+
 impl<A: SolverAdaptor> Solver<A, ExecutionSuccess> {
     pub fn stats(&self) -> SolverStats {
         self.state.stats.clone()
     }
 
+    /// Whether the solver completed its search, and why not if it did not.
+    pub fn status(&self) -> &SearchStatus {
+        &self.state.status
+    }
+
     // Saves this solvers stats to the global context as a "solver run"
     pub fn save_stats_to_context(&self) {
         #[allow(clippy::unwrap_used)]
@@ -404,6 +537,10 @@ pub enum SolverError {
 
     #[error("error during solver execution: {0}")]
     Runtime(String),
+
+    /// A solution found by the solver is not a solution of the model it was validated against.
+    #[error("invalid solution: {0}")]
+    InvalidSolution(String),
 }
 
 /// Returned from [SolverAdaptor] when solving is successful.
@@ -412,6 +549,7 @@ pub struct SolveSuccess {
     status: SearchStatus,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchStatus {
     /// The search was complete (i.e. the solver found all possible solutions)
     Complete(SearchComplete),
@@ -420,15 +558,19 @@ pub enum SearchStatus {
 }
 
 #[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchIncomplete {
     Timeout,
     UserTerminated,
+    /// Search was stopped after finding [`SolveOptions::max_solutions`] solutions.
+    SolutionLimitReached,
     #[doc(hidden)]
     /// This variant should not be matched - it exists to simulate non-exhaustiveness of this enum.
     __NonExhaustive,
 }
 
 #[non_exhaustive]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum SearchComplete {
     HasSolutions,
     NoSolutions,
@@ -436,3 +578,60 @@ pub enum SearchComplete {
     /// This variant should not be matched - it exists to simulate non-exhaustiveness of this enum.
     __NonExhaustive,
 }
+
+/// Enforces [`SolveOptions::max_solutions`] by wrapping the user callback.
+///
+/// Adaptors see a stop at the solution limit as the callback returning `false`, so report it as
+/// [`SearchIncomplete::UserTerminated`]; this is corrected by [`SolutionLimit::adjust_status`].
+struct SolutionLimit {
+    max_solutions: Option<u64>,
+    n_solutions: AtomicU64,
+    reached: AtomicBool,
+}
+
+impl SolutionLimit {
+    fn new(max_solutions: Option<u64>) -> SolutionLimit {
+        SolutionLimit {
+            max_solutions,
+            n_solutions: AtomicU64::new(0),
+            reached: AtomicBool::new(false),
+        }
+    }
+
+    /// Counts a solution, calling the user callback if it is within the limit.
+    ///
+    /// Returns whether search should continue.
+    fn check(&self, callback: impl FnOnce() -> bool) -> bool {
+        let Some(max_solutions) = self.max_solutions else {
+            return callback();
+        };
+
+        let n_solutions = self.n_solutions.fetch_add(1, Ordering::SeqCst) + 1;
+        if n_solutions > max_solutions {
+            self.reached.store(true, Ordering::SeqCst);
+            return false;
+        }
+
+        if !callback() {
+            return false;
+        }
+
+        if n_solutions == max_solutions {
+            self.reached.store(true, Ordering::SeqCst);
+            return false;
+        }
+
+        true
+    }
+
+    fn adjust_status(&self, status: SearchStatus) -> SearchStatus {
+        match status {
+            SearchStatus::Incomplete(SearchIncomplete::UserTerminated)
+                if self.reached.load(Ordering::SeqCst) =>
+            {
+                SearchStatus::Incomplete(SearchIncomplete::SolutionLimitReached)
+            }
+            x => x,
+        }
+    }
+}
@@ -27,6 +27,14 @@ pub trait ModelModifier: private::Sealed {
     fn add_variable(&self, name: Name, domain: Domain) -> Result<(), ModificationFailure> {
         Err(ModificationFailure::OpNotSupported)
     }
+
+    /// Replaces the assumptions used by later runs of the solver.
+    ///
+    /// Assumptions are boolean literals that must hold in solutions. Unlike constraints, they
+    /// can be replaced again later, which incremental solvers support without being rebuilt.
+    fn set_assumptions(&self, assumptions: Vec<Expression>) -> Result<(), ModificationFailure> {
+        Err(ModificationFailure::OpNotSupported)
+    }
 }
 
 /// A [`ModelModifier`] for a solver that does not support incremental solving. Returns
@@ -0,0 +1,117 @@
+//! Checking solutions against a model, usually the model as parsed before rewriting.
+//!
+//! Rewriter bugs can give models whose solutions are not solutions of the original problem. Use
+//! [`Solver::with_validation`](super::Solver::with_validation) to check every solution found by a
+//! solver against the original model, or [validate_solution] to check a single solution.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use crate::ast::{Constant, Expression, Name, SymbolTable};
+use crate::Model;
+
+use super::SolverError;
+
+/// Checks that a solution satisfies every constraint of the given model, and gives each of its
+/// variables a value in its domain.
+///
+/// Auxiliary variables, which have [`Name::MachineName`]s, are ignored, both in the model and in
+/// the solution.
+///
+/// Returns a [`SolverError::InvalidSolution`] naming the first violated constraint, if any.
+pub fn validate_solution(
+    model: &Model,
+    solution: &HashMap<Name, Constant>,
+) -> Result<(), SolverError> {
+    check(&model.variables, &model.get_constraints_vec(), solution)
+}
+
+fn check(
+    variables: &SymbolTable,
+    constraints: &[Expression],
+    solution: &HashMap<Name, Constant>,
+) -> Result<(), SolverError> {
+    let solution: HashMap<Name, Constant> = solution
+        .iter()
+        .filter(|(name, _)| !matches!(name, Name::MachineName(_)))
+        .map(|(name, value)| (name.clone(), value.clone()))
+        .collect();
+
+    for (name, var) in variables {
+        if let Name::MachineName(_) = name {
+            continue;
+        }
+        let Some(value) = solution.get(name) else {
+            return Err(SolverError::InvalidSolution(format!(
+                "the solution does not give a value for {}",
+                name
+            )));
+        };
+        if !var.domain.contains(value) {
+            return Err(SolverError::InvalidSolution(format!(
+                "{} = {} is not in the domain {:?}",
+                name, value, var.domain
+            )));
+        }
+    }
+
+    for constraint in constraints {
+        match constraint.eval(&solution) {
+            Ok(Constant::Bool(true)) | Ok(Constant::Int(1)) => {}
+            Ok(_) => {
+                return Err(SolverError::InvalidSolution(format!(
+                    "the solution violates the constraint {}",
+                    constraint
+                )))
+            }
+            Err(e) => {
+                return Err(SolverError::InvalidSolution(format!(
+                    "could not check the constraint {}: {}",
+                    constraint, e
+                )))
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Checks each solution found during search, stopping search at the first invalid solution.
+pub(super) struct SolutionValidator {
+    variables: SymbolTable,
+    constraints: Vec<Expression>,
+    error: Mutex<Option<SolverError>>,
+}
+
+impl SolutionValidator {
+    pub(super) fn new(model: &Model) -> Self {
+        SolutionValidator {
+            variables: model.variables.clone(),
+            constraints: model.get_constraints_vec(),
+            error: Mutex::new(None),
+        }
+    }
+
+    /// Validates the solution, then calls `f` if it is valid. Returns `false` to stop search if
+    /// the solution is invalid.
+    pub(super) fn check(
+        &self,
+        solution: &HashMap<Name, Constant>,
+        f: impl FnOnce() -> bool,
+    ) -> bool {
+        match check(&self.variables, &self.constraints, solution) {
+            Ok(()) => f(),
+            Err(e) => {
+                #[allow(clippy::unwrap_used)]
+                self.error.lock().unwrap().get_or_insert(e);
+                false
+            }
+        }
+    }
+
+    /// The error for the first invalid solution found, if any.
+    pub(super) fn error(&self) -> Option<SolverError> {
+        #[allow(clippy::unwrap_used)]
+        self.error.lock().unwrap().clone()
+    }
+}
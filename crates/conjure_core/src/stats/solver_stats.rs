@@ -1,3 +1,5 @@
+use std::path::PathBuf;
+
 use schemars::JsonSchema;
 use serde::Serialize;
 use serde_with::skip_serializing_none;
@@ -21,11 +23,41 @@ pub struct SolverStats {
     /// The solver adaptor used for this run.
     pub solver_adaptor: Option<String>,
 
+    /// The options given to the solver for this run, as command line arguments to the solver.
+    pub solver_args: Option<Vec<String>>,
+
     // NOTE (niklasdewally): these fields are copied from the list in Savile Row
     pub nodes: Option<u64>,
     pub satisfiable: Option<bool>,
     pub sat_vars: Option<u64>,
     pub sat_clauses: Option<u64>,
+
+    /// Time spent searching in seconds, as measured by the solver.
+    pub solver_solve_time: Option<f64>,
+
+    /// Time spent setting up the model before search in seconds, as measured by the solver.
+    pub solver_setup_time: Option<f64>,
+
+    /// The number of solutions found.
+    pub solutions_found: Option<u64>,
+
+    /// Whether the solver stopped because it reached its time limit.
+    pub timed_out: Option<bool>,
+
+    /// The number of times the SAT solver was called.
+    pub sat_solves: Option<u64>,
+
+    /// The average length of a clause given to the SAT solver.
+    pub sat_avg_clause_len: Option<f64>,
+
+    /// The number of clauses added by cardinality and pseudo-boolean encodings.
+    pub sat_pb_clauses: Option<u64>,
+
+    /// The number of auxiliary variables added by cardinality and pseudo-boolean encodings.
+    pub sat_pb_aux_vars: Option<u64>,
+
+    /// The file the SAT solver wrote its proof of unsatisfiability to, if one was requested.
+    pub sat_proof_file: Option<PathBuf>,
 }
 
 impl SolverStats {
@@ -14,8 +14,11 @@ fn main() {
     let out_dir = env::var("OUT_DIR").unwrap();
 
     println!("cargo:rustc-link-search=all={}/build", out_dir);
+    // libminion_ext uses libminion, so must come first.
+    println!("cargo:rustc-link-lib=static=minion_ext");
     println!("cargo:rustc-link-lib=static=minion");
     println!("cargo:rerun-if-changed=vendor");
+    println!("cargo:rerun-if-changed=wrapper");
     println!("cargo:rerun-if-changed=build.rs");
     println!("cargo:rerun-if-changed=build.sh");
     println!("cargo:rerun-if-env-changed=DEBUG_MINION");
@@ -62,8 +65,8 @@ fn bind() {
     // the resulting bindings.
     let bindings = bindgen::Builder::default()
         // The input header we would like to generate
-        // bindings for.
-        .header("vendor/minion/libwrapper.h")
+        // bindings for. This includes libwrapper.h.
+        .header("wrapper/libwrapper_ext.h")
         // Make all templates opaque as reccomended by bindgen
         .opaque_type("std::.*")
         // Manually allow C++ functions to stop bindgen getting confused.
@@ -111,6 +114,14 @@ fn bind() {
         .allowlist_function("vec_vec_int_push_back")
         .allowlist_function("vec_vec_int_free")
         .allowlist_function("TableOut_get")
+        .allowlist_function("runMinionKeepingErrors")
+        .allowlist_function("getLastError")
+        .allowlist_function("searchOrder_setValueOrder")
+        .allowlist_function("instance_propagateRoot")
+        .allowlist_function("vec_vec_int_size")
+        .allowlist_function("vec_vec_int_at")
+        .allowlist_function("vec_int_size")
+        .allowlist_function("vec_int_at")
         .clang_arg(format!("-I{}/build/src/", out_dir)) // generated from configure.py
         .clang_arg("-Ivendor/minion/")
         .clang_arg("-DLIBMINION")
@@ -31,3 +31,15 @@ fi
 echo "------ BUILD STEP ------"
 cd "$OUT_DIR/build"
 make
+
+echo "------ WRAPPER STEP ------"
+# additions to libwrapper, built with the same flags that bindgen uses in build.rs.
+cd "$OUT_DIR/build"
+
+WRAPPER_FLAGS=(-std=gnu++14 -fPIC -O2 -DLIBMINION -I"$OUT_DIR/build/src/" -I"$SCRIPT_DIR/vendor/minion/")
+if [[ ${DEBUG_MINION-default} != "default" ]]; then
+  WRAPPER_FLAGS+=(-g -D_GLIBCXX_DEBUG -DMORE_SEARCH_INFO -DMINION_DEBUG)
+fi
+
+"${CXX:-c++}" "${WRAPPER_FLAGS[@]}" -c "$SCRIPT_DIR/wrapper/libwrapper_ext.cpp" -o libwrapper_ext.o
+ar rcs libminion_ext.a libwrapper_ext.o
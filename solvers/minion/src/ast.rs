@@ -12,6 +12,18 @@ pub type TwoVars = (Var, Var);
 pub struct Model {
     pub named_variables: SymbolTable,
     pub constraints: Vec<Constraint>,
+
+    /// The search orders of the model, as given by Minion's `VARORDER` statements.
+    ///
+    /// If empty, all variables are searched on in the order that they were added to the
+    /// [SymbolTable]. Otherwise, variables not in any search order are auxiliary: they are
+    /// searched on last, and only one assignment to them is found for each solution.
+    pub search_orders: Vec<SearchOrder>,
+
+    /// The variables returned in each solution, as given by Minion's `PRINT` statement.
+    ///
+    /// If `None`, all variables are returned.
+    pub print_vars: Option<Vec<VarName>>,
 }
 
 impl Model {
@@ -20,6 +32,8 @@ impl Model {
         Model {
             named_variables: SymbolTable::new(),
             constraints: Vec::new(),
+            search_orders: Vec::new(),
+            print_vars: None,
         }
     }
 }
@@ -30,6 +44,55 @@ impl Default for Model {
     }
 }
 
+/// A Minion `VARORDER` statement.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchOrder {
+    /// The variables to search on.
+    pub vars: Vec<VarName>,
+
+    /// The heuristic used to pick the next variable to branch on.
+    pub var_order: VarOrder,
+
+    /// The order in which values are tried for each variable.
+    pub value_order: ValueOrder,
+}
+
+impl SearchOrder {
+    /// Creates a static search order over the given variables, trying values in ascending order.
+    pub fn new(vars: Vec<VarName>) -> SearchOrder {
+        SearchOrder {
+            vars,
+            var_order: VarOrder::Static,
+            value_order: ValueOrder::Ascending,
+        }
+    }
+}
+
+/// Variable ordering heuristics.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum VarOrder {
+    /// Branch on variables in the given order (`STATIC`).
+    Static,
+
+    /// Branch on the variable with the smallest domain first (`SDF`).
+    Sdf,
+
+    /// Branch on variables involved in recent conflicts first (`CONFLICT`).
+    Conflict,
+}
+
+/// Value ordering heuristics.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum ValueOrder {
+    /// Try the smallest value first (`a` in a `VALORDER`).
+    Ascending,
+
+    /// Try the largest value first (`d` in a `VALORDER`).
+    Descending,
+}
+
 /// All supported Minion constraints.
 #[non_exhaustive]
 #[derive(Debug, Clone, PartialEq, Eq)]
@@ -155,7 +218,8 @@ pub enum VarDomain {
 pub struct SymbolTable {
     table: HashMap<VarName, VarDomain>,
 
-    // for now doubles both as Minion's SearchOrder and print order
+    // the order variables were declared in.
+    // this is the default search and print order if the model does not give one.
     var_order: Vec<VarName>,
 }
 
@@ -193,6 +257,8 @@ impl SymbolTable {
     }
 
     /// Gets the canonical ordering of variables.
+    ///
+    /// This is the order in which variables were added to the symbol table.
     pub fn get_variable_order(&self) -> Vec<VarName> {
         self.var_order.clone()
     }
@@ -0,0 +1,7 @@
+//! A worker process for [minion_rs::worker::WorkerPool].
+
+use std::process::ExitCode;
+
+fn main() -> ExitCode {
+    minion_rs::worker::worker_main()
+}
@@ -9,13 +9,17 @@ use crate::ffi;
 #[non_exhaustive]
 pub enum MinionError {
     /// An error has occurred during the execution of Minion.
-    #[error("runtime error: `{0}.to_string()`")]
+    #[error("runtime error: {0}")]
     RuntimeError(#[from] RuntimeError),
 
     /// The input model uses Minion features that are not yet implemented in `minion_rs`.
     #[error("not implemented: {0}")]
     NotImplemented(String),
 
+    /// A textual Minion file could not be parsed.
+    #[error("parse error: {0}")]
+    ParseError(#[from] ParseError),
+
     /// Catch-all error.
     #[error(transparent)]
     Other(#[from] anyhow::Error), // source and Display delegate to anyhow::Error
@@ -23,7 +27,9 @@ pub enum MinionError {
 
 /// Errors thrown by Minion during execution.
 ///
-/// These represent internal Minion C++ exceptions translated into Rust.
+/// These represent internal Minion C++ exceptions translated into Rust. The message of the
+/// exception, and any diagnostics Minion printed while running, such as the constraint or variable
+/// at fault, are kept in `details`.
 ///
 /// Invalid usage of this library should throw an error before Minion is even run. Therefore, these
 /// should be quite rare. Consider creating an issue on
@@ -33,22 +39,81 @@ pub enum MinionError {
 pub enum RuntimeError {
     // These closely follow the ReturnCodes found in Minion's libwrapper.cpp.
     /// The model given to Minion is invalid.
-    #[error("the given instance is invalid")]
-    InvalidInstance,
+    #[error("the given instance is invalid{}", fmt_details(.details))]
+    InvalidInstance { details: Option<String> },
 
     /// An unknown error has occurred.
-    #[error("an unknown error has occurred while running minion")]
-    UnknownError,
+    #[error("an unknown error has occurred while running minion{}", fmt_details(.details))]
+    UnknownError { details: Option<String> },
+}
+
+impl RuntimeError {
+    /// Converts one of Minion's ReturnCodes to its appropriate error, with the given diagnostics.
+    pub fn from_return_code(return_code: u32, details: Option<String>) -> Self {
+        match return_code {
+            #[allow(non_upper_case_globals)]
+            ffi::ReturnCodes_INVALID_INSTANCE => RuntimeError::InvalidInstance { details },
+            _ => RuntimeError::UnknownError { details },
+        }
+    }
+
+    /// The diagnostics printed by Minion when this error occurred, if any.
+    pub fn details(&self) -> Option<&str> {
+        match self {
+            RuntimeError::InvalidInstance { details } | RuntimeError::UnknownError { details } => {
+                details.as_deref()
+            }
+        }
+    }
 }
 
 // Minion's ReturnCodes are passed over FFI as ints.
 // Convert them to their appropriate error.
 impl From<u32> for RuntimeError {
     fn from(return_code: u32) -> Self {
-        match return_code {
-            #[allow(non_upper_case_globals)]
-            ffi::ReturnCodes_INVALID_INSTANCE => RuntimeError::InvalidInstance,
-            _ => RuntimeError::UnknownError,
-        }
+        RuntimeError::from_return_code(return_code, None)
     }
 }
+
+fn fmt_details(details: &Option<String>) -> String {
+    match details {
+        Some(x) => format!(": {}", x),
+        None => String::new(),
+    }
+}
+
+/// An error found while parsing a textual Minion file.
+///
+/// Lines and columns are 1-indexed, and point to the start of the offending item.
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+#[error("{line}:{column}: {kind}")]
+pub struct ParseError {
+    pub line: usize,
+    pub column: usize,
+    pub kind: ParseErrorKind,
+}
+
+/// The kinds of [ParseError].
+#[derive(Debug, Error, Clone, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ParseErrorKind {
+    /// The input is not valid Minion syntax.
+    #[error("syntax error: {0}")]
+    Syntax(String),
+
+    /// The constraint is valid Minion, but has no representation in [Constraint](crate::ast::Constraint).
+    #[error("unsupported constraint `{0}`")]
+    UnsupportedConstraint(String),
+
+    /// The input uses a Minion feature that `minion_rs` does not support.
+    #[error("unsupported feature: {0}")]
+    Unsupported(String),
+
+    /// A variable is used without being declared in a **VARIABLES** section.
+    #[error("unknown variable `{0}`")]
+    UnknownVariable(String),
+
+    /// A variable is declared more than once.
+    #[error("variable `{0}` is defined twice")]
+    DuplicateVariable(String),
+}
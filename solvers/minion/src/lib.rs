@@ -29,18 +29,11 @@
 //! use minion_rs::ast::*;
 //! use minion_rs::run_minion;
 //! use std::collections::HashMap;
-//! use std::sync::Mutex;
 //!
 //! // Get solutions out of Minion.
-//! // See the documentation for Callback for details.
+//! // See the documentation for run_minion for details.
 //!
-//! static ALL_SOLUTIONS: Mutex<Vec<HashMap<VarName,Constant>>>  = Mutex::new(vec![]);
-//!
-//! fn callback(solutions: HashMap<VarName,Constant>) -> bool {
-//!     let mut guard = ALL_SOLUTIONS.lock().unwrap();
-//!     guard.push(solutions);
-//!     true
-//! }
+//! let mut all_solutions: Vec<HashMap<VarName,Constant>> = vec![];
 //!
 //! // Build and run the model.
 //! let mut model = Model::new();
@@ -82,18 +75,20 @@
 //! model.constraints.push(geq);
 //! model.constraints.push(ineq);
 //!
-//! let res = run_minion(model, callback);
+//! let res = run_minion(model, |solutions| {
+//!     all_solutions.push(solutions);
+//!     true
+//! });
 //! res.expect("Error occurred");
 //!
 //! // Get solutions
-//! let guard = ALL_SOLUTIONS.lock().unwrap();
-//! let solution_set_1 = &(guard.get(0).unwrap());
+//! let solution_set_1 = &(all_solutions.get(0).unwrap());
 //!
 //! let x1 = solution_set_1.get("x").unwrap();
 //! let y1 = solution_set_1.get("y").unwrap();
 //! let z1 = solution_set_1.get("z").unwrap();
 //!
-//! assert_eq!(guard.len(),1);
+//! assert_eq!(all_solutions.len(),1);
 //! assert_eq!(*x1,Constant::Integer(1));
 //! assert_eq!(*y1,Constant::Integer(2));
 //! assert_eq!(*z1,Constant::Integer(1));
@@ -101,9 +96,39 @@
 //!
 //! ## `PRINT` and `VARORDER`
 //!
-//! These bindings have no replacement for Minion's `PRINT` and `VARORDER` statements - any
-//! variable given to the model that does not have a constant value is considered a search
-//! variable. Solutions are returned through the [callback function](Callback) as a `HashMap`.
+//! By default, all variables in the model are searched on, and all are returned through the
+//! [callback function](run_minion) as a `HashMap`.
+//!
+//! Minion's `VARORDER` and `PRINT` statements are represented by
+//! [Model::search_orders](ast::Model::search_orders) and
+//! [Model::print_vars](ast::Model::print_vars). Variables not in any search order are treated as
+//! auxiliary, and only variables in the print list are returned to the callback.
+//!
+//! ## Search options
+//!
+//! [run_minion_with_options] takes a [SearchOptions], which sets limits on search, the random
+//! seed, restarts, and the level of preprocessing.
+//!
+//! ## Writing Minion files
+//!
+//! [print::write_minion_file] writes a [Model](ast::Model) out in Minion's textual input format.
+//! This is useful for reproducing a run with the standalone Minion binary.
+//!
+//! ## Parsing Minion files
+//!
+//! [parse::parse_minion_file] reads a `MINION 3` input file into a [Model](ast::Model), reporting
+//! the line and column of any syntax errors or unsupported constraints.
+//!
+//! ## Domain filtering
+//!
+//! [propagate::propagate_root] runs Minion's propagation without search, returning the filtered
+//! domain of each variable. This is useful for shrinking domains before solving.
+//!
+//! ## Running several models at once
+//!
+//! Minion keeps its state in globals, so [run_minion] runs one model at a time. To solve several
+//! models at once, use a [worker::WorkerPool], which runs each model in a separate
+//! `minion_worker` process.
 
 pub use run::*;
 
@@ -111,9 +136,17 @@ pub mod error;
 mod ffi;
 
 pub mod ast;
+pub mod parse;
+pub mod print;
+pub mod propagate;
 mod run;
 
+mod options;
+pub use options::*;
+
 mod scoped_ptr;
 
 mod wrappers;
 pub use wrappers::*;
+
+pub mod worker;
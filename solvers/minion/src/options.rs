@@ -0,0 +1,181 @@
+//! Options controlling how Minion searches.
+
+use std::fmt::Display;
+use std::str::FromStr;
+
+use crate::ffi;
+
+/// Options for a run of Minion.
+///
+/// These correspond to fields of Minion's `SearchOptions` and `SearchMethod`, and to the command
+/// line flags given by [SearchOptions::to_args]. The default value leaves all of Minion's defaults
+/// in place, except that all solutions are found.
+///
+/// # Examples
+///
+/// ```
+/// use minion_rs::{PropagationLevel, SearchOptions};
+///
+/// let mut options = SearchOptions::default();
+/// options.node_limit = Some(1000);
+/// options.random_seed = Some(42);
+/// options.preprocess = Some(PropagationLevel::Sac);
+///
+/// assert_eq!(
+///     options.to_args(),
+///     vec![
+///         "-nodelimit",
+///         "1000",
+///         "-findallsols",
+///         "-randomseed",
+///         "42",
+///         "-preprocess",
+///         "SAC"
+///     ]
+/// );
+/// ```
+#[non_exhaustive]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SearchOptions {
+    /// Stop search after this many nodes (`-nodelimit`).
+    pub node_limit: Option<u64>,
+
+    /// Stop search after this many seconds of wall-clock time (`-timelimit`).
+    pub time_limit_s: Option<u64>,
+
+    /// Stop search after this many solutions (`-sollimit`).
+    ///
+    /// If `None`, all solutions are found.
+    pub solution_limit: Option<u64>,
+
+    /// The seed for Minion's random number generator (`-randomseed`).
+    pub random_seed: Option<i32>,
+
+    /// Restart search periodically (`-restarts`).
+    pub restarts: bool,
+
+    /// The level of propagation to do before search (`-preprocess`).
+    pub preprocess: Option<PropagationLevel>,
+}
+
+impl SearchOptions {
+    /// The Minion command line flags that give these options.
+    ///
+    /// Together with [write_minion_file](crate::print::write_minion_file), these can be used to
+    /// reproduce a run with the standalone Minion binary.
+    pub fn to_args(&self) -> Vec<String> {
+        let mut args: Vec<String> = vec![];
+
+        if let Some(x) = self.node_limit {
+            args.extend(["-nodelimit".into(), x.to_string()]);
+        }
+
+        if let Some(x) = self.time_limit_s {
+            args.extend(["-timelimit".into(), x.to_string()]);
+        }
+
+        if let Some(x) = self.solution_limit {
+            args.extend(["-sollimit".into(), x.to_string()]);
+        } else {
+            args.push("-findallsols".into());
+        }
+
+        if let Some(x) = self.random_seed {
+            args.extend(["-randomseed".into(), x.to_string()]);
+        }
+
+        if self.restarts {
+            args.push("-restarts".into());
+        }
+
+        if let Some(x) = self.preprocess {
+            args.extend(["-preprocess".into(), x.to_string()]);
+        }
+
+        args
+    }
+
+    /// Sets these options on Minion's `SearchOptions` and `SearchMethod` objects.
+    pub(crate) unsafe fn apply(
+        &self,
+        search_opts: *mut ffi::SearchOptions,
+        search_method: *mut ffi::SearchMethod,
+    ) {
+        // Minion uses 0 to mean "no limit" for node and time limits, and -1 for solution limits.
+        (*search_opts).nodelimit = self.node_limit.unwrap_or(0) as _;
+        (*search_opts).time_limit = self.time_limit_s.unwrap_or(0) as _;
+        (*search_opts).time_limit_is_CPU_time = false;
+        (*search_opts).sollimit = self.solution_limit.map(|x| x as i64).unwrap_or(-1) as _;
+
+        if let Some(x) = self.random_seed {
+            (*search_method).random_seed = x as _;
+        }
+
+        (*search_opts).restart.active = self.restarts;
+
+        if let Some(x) = self.preprocess {
+            (*search_method).preprocess = x.to_raw();
+        }
+    }
+}
+
+/// Levels of propagation that Minion can do before search.
+#[non_exhaustive]
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum PropagationLevel {
+    /// Generalised arc consistency.
+    Gac,
+
+    /// Singleton arc consistency.
+    Sac,
+
+    /// Singleton singleton arc consistency.
+    Ssac,
+
+    /// Singleton arc consistency, on variable bounds only.
+    SacBounds,
+
+    /// Singleton singleton arc consistency, on variable bounds only.
+    SsacBounds,
+}
+
+impl PropagationLevel {
+    fn to_raw(self) -> ffi::PropagationLevel {
+        match self {
+            PropagationLevel::Gac => ffi::PropagationLevel_PropLevel_GAC,
+            PropagationLevel::Sac => ffi::PropagationLevel_PropLevel_SAC,
+            PropagationLevel::Ssac => ffi::PropagationLevel_PropLevel_SSAC,
+            PropagationLevel::SacBounds => ffi::PropagationLevel_PropLevel_SACBounds,
+            PropagationLevel::SsacBounds => ffi::PropagationLevel_PropLevel_SSACBounds,
+        }
+    }
+}
+
+impl Display for PropagationLevel {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let name = match self {
+            PropagationLevel::Gac => "GAC",
+            PropagationLevel::Sac => "SAC",
+            PropagationLevel::Ssac => "SSAC",
+            PropagationLevel::SacBounds => "SACBounds",
+            PropagationLevel::SsacBounds => "SSACBounds",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for PropagationLevel {
+    type Err = String;
+
+    /// Parses a propagation level from its name in Minion's `-preprocess` flag, ignoring case.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_lowercase().as_str() {
+            "gac" => Ok(PropagationLevel::Gac),
+            "sac" => Ok(PropagationLevel::Sac),
+            "ssac" => Ok(PropagationLevel::Ssac),
+            "sacbounds" => Ok(PropagationLevel::SacBounds),
+            "ssacbounds" => Ok(PropagationLevel::SsacBounds),
+            _ => Err(format!("unknown propagation level `{}`", s)),
+        }
+    }
+}
@@ -0,0 +1,1142 @@
+//! Parsing textual Minion files into [Model]s.
+//!
+//! [parse_minion_file] reads the subset of the `MINION 3` input format that can be represented as
+//! a [Model]; this is the inverse of [write_minion_file](crate::print::write_minion_file).
+//!
+//! The following are supported:
+//!
+//! * `BOOL`, `BOUND`, `DISCRETE` and `SPARSEBOUND` variables. Matrices and aliases are not.
+//! * `**TUPLELIST**` sections and inline tuple lists, as long as all tuples have two elements.
+//! * Every constraint in [Constraint].
+//!
+//! In the `**SEARCH**` section, `PRINT` statements and `STATIC`, `SDF`, `CONFLICT` and `AUX`
+//! variable orderings are supported. Variables listed in a `VARORDER AUX` are not added to
+//! [Model::search_orders], as any variable not in a search order is already auxiliary. A
+//! `VALORDER` must be either all ascending or all descending.
+//!
+//! Errors are reported as a [ParseError], which gives the line and column of the offending item.
+
+use std::collections::HashMap;
+
+use crate::ast::{
+    Constant, Constraint, Model, SearchOrder, Tuple, ValueOrder, Var, VarDomain, VarName, VarOrder,
+};
+use crate::error::{ParseError, ParseErrorKind};
+
+type Result<T> = std::result::Result<T, ParseError>;
+
+/// Parses a `MINION 3` input file into a [Model].
+///
+/// # Examples
+///
+/// ```
+/// use minion_rs::ast::*;
+/// use minion_rs::parse::parse_minion_file;
+///
+/// let model = parse_minion_file(
+///     "MINION 3
+///      **VARIABLES**
+///      BOUND x {1..3}
+///      BOOL y
+///      **CONSTRAINTS**
+///      ineq(x, 2, 0)
+///      **EOF**",
+/// )
+/// .unwrap();
+///
+/// assert_eq!(
+///     model.named_variables.get_vartype("x".to_owned()),
+///     Some(VarDomain::Bound(1, 3))
+/// );
+/// assert_eq!(
+///     model.constraints,
+///     vec![Constraint::Ineq(
+///         Var::NameRef("x".to_owned()),
+///         Var::ConstantAsVar(2),
+///         Constant::Integer(0)
+///     )]
+/// );
+/// ```
+///
+/// Unsupported constraints are reported with their location:
+///
+/// ```
+/// use minion_rs::error::ParseErrorKind;
+/// use minion_rs::parse::parse_minion_file;
+///
+/// let err = parse_minion_file(
+///     "MINION 3
+/// **VARIABLES**
+/// BOOL x
+/// **CONSTRAINTS**
+/// haggisgac([x], {})
+/// **EOF**",
+/// )
+/// .unwrap_err();
+///
+/// assert_eq!((err.line, err.column), (5, 1));
+/// assert_eq!(
+///     err.kind,
+///     ParseErrorKind::UnsupportedConstraint("haggisgac".to_owned())
+/// );
+/// ```
+pub fn parse_minion_file(input: &str) -> Result<Model> {
+    Parser::new(input).parse_file()
+}
+
+struct Parser {
+    chars: Vec<char>,
+    pos: usize,
+    line: usize,
+    column: usize,
+    model: Model,
+    tuple_lists: HashMap<String, Vec<Tuple>>,
+}
+
+impl Parser {
+    fn new(input: &str) -> Parser {
+        Parser {
+            chars: input.chars().collect(),
+            pos: 0,
+            line: 1,
+            column: 1,
+            model: Model::new(),
+            tuple_lists: HashMap::new(),
+        }
+    }
+
+    fn parse_file(mut self) -> Result<Model> {
+        let header = self.ident()?;
+        if header != "MINION" {
+            return self.error(ParseErrorKind::Syntax(format!(
+                "expected `MINION 3`, found `{header}`"
+            )));
+        }
+
+        let (line, column) = self.position();
+        let version = self.int()?;
+        if version != 3 {
+            return Err(ParseError {
+                line,
+                column,
+                kind: ParseErrorKind::Unsupported(format!("input format version {version}")),
+            });
+        }
+
+        loop {
+            self.skip_whitespace();
+            if self.peek().is_none() || self.eat("**EOF**") {
+                break;
+            }
+
+            self.expect("**")?;
+            let (line, column) = self.position();
+            let section = self.ident()?;
+            self.expect("**")?;
+
+            match section.as_str() {
+                "VARIABLES" => self.parse_variables()?,
+                "SEARCH" => self.parse_search()?,
+                "CONSTRAINTS" => self.parse_constraints()?,
+                "TUPLELIST" => self.parse_tuple_lists()?,
+                _ => {
+                    return Err(ParseError {
+                        line,
+                        column,
+                        kind: ParseErrorKind::Unsupported(format!("**{section}** sections")),
+                    })
+                }
+            }
+        }
+
+        Ok(self.model)
+    }
+
+    /*****************************/
+    /*        Sections           */
+    /*****************************/
+
+    fn parse_variables(&mut self) -> Result<()> {
+        while !self.at_section_end() {
+            let (line, column) = self.position();
+            let decl = self.ident()?;
+            let (name_line, name_column) = self.position();
+
+            let (name, domain) = match decl.as_str() {
+                "BOOL" => (self.var_name()?, VarDomain::Bool),
+                "BOUND" => {
+                    let name = self.var_name()?;
+                    let (lo, hi) = self.range()?;
+                    (name, VarDomain::Bound(lo, hi))
+                }
+                "DISCRETE" => {
+                    let name = self.var_name()?;
+                    let (lo, hi) = self.range()?;
+                    (name, VarDomain::Discrete(lo, hi))
+                }
+                "SPARSEBOUND" => {
+                    let name = self.var_name()?;
+                    let (values_line, values_column) = self.position();
+                    self.expect("{")?;
+                    let values = self.comma_separated("}", Self::int)?;
+                    match values[..] {
+                        [a, b] => (name, VarDomain::SparseBound(a, b)),
+                        _ => {
+                            return Err(ParseError {
+                                line: values_line,
+                                column: values_column,
+                                kind: ParseErrorKind::Unsupported(
+                                    "SPARSEBOUND domains without exactly two values".to_owned(),
+                                ),
+                            })
+                        }
+                    }
+                }
+                "ALIAS" => {
+                    return Err(ParseError {
+                        line,
+                        column,
+                        kind: ParseErrorKind::Unsupported("ALIAS declarations".to_owned()),
+                    })
+                }
+                _ => {
+                    return Err(ParseError {
+                        line,
+                        column,
+                        kind: ParseErrorKind::Syntax(format!(
+                            "expected a variable declaration, found `{decl}`"
+                        )),
+                    })
+                }
+            };
+
+            if self
+                .model
+                .named_variables
+                .add_var(name.clone(), domain)
+                .is_none()
+            {
+                return Err(ParseError {
+                    line: name_line,
+                    column: name_column,
+                    kind: ParseErrorKind::DuplicateVariable(name),
+                });
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_search(&mut self) -> Result<()> {
+        // VALORDER applies to the search order given by the previous VARORDER statement.
+        // this is false before any VARORDER, and after a VARORDER AUX.
+        let mut can_set_value_order = false;
+
+        while !self.at_section_end() {
+            let (line, column) = self.position();
+            let statement = self.ident()?;
+
+            match statement.as_str() {
+                "PRINT" => {
+                    self.model.print_vars = if self.eat("ALL") {
+                        None
+                    } else if self.eat("NONE") {
+                        Some(vec![])
+                    } else {
+                        Some(self.nested_var_list()?)
+                    };
+                }
+                "VARORDER" => {
+                    let (line, column) = self.position();
+                    let var_order = if self.at("[") {
+                        Some(VarOrder::Static)
+                    } else {
+                        match self.ident()?.as_str() {
+                            "STATIC" => Some(VarOrder::Static),
+                            "SDF" => Some(VarOrder::Sdf),
+                            "CONFLICT" => Some(VarOrder::Conflict),
+                            // variables not in any other search order are already auxiliary.
+                            "AUX" => None,
+                            x => {
+                                return Err(ParseError {
+                                    line,
+                                    column,
+                                    kind: ParseErrorKind::Unsupported(format!(
+                                        "`{x}` variable ordering"
+                                    )),
+                                })
+                            }
+                        }
+                    };
+
+                    self.expect("[")?;
+                    let vars = self.comma_separated("]", Self::named_var)?;
+
+                    can_set_value_order = var_order.is_some();
+                    if let Some(var_order) = var_order {
+                        self.model.search_orders.push(SearchOrder {
+                            vars,
+                            var_order,
+                            value_order: ValueOrder::Ascending,
+                        });
+                    }
+                }
+                "VALORDER" => {
+                    self.expect("[")?;
+                    let value_orders = self.comma_separated("]", Self::ident)?;
+
+                    let search_order = match self.model.search_orders.last_mut() {
+                        Some(x) if can_set_value_order => x,
+                        _ => {
+                            return Err(ParseError {
+                                line,
+                                column,
+                                kind: ParseErrorKind::Syntax(
+                                    "VALORDER must follow a VARORDER".to_owned(),
+                                ),
+                            })
+                        }
+                    };
+
+                    if value_orders.len() != search_order.vars.len() {
+                        return Err(ParseError {
+                            line,
+                            column,
+                            kind: ParseErrorKind::Syntax(format!(
+                                "VALORDER has {} values, but its VARORDER has {} variables",
+                                value_orders.len(),
+                                search_order.vars.len()
+                            )),
+                        });
+                    }
+
+                    search_order.value_order = if value_orders.iter().all(|x| x == "a") {
+                        ValueOrder::Ascending
+                    } else if value_orders.iter().all(|x| x == "d") {
+                        ValueOrder::Descending
+                    } else {
+                        return Err(ParseError {
+                            line,
+                            column,
+                            kind: ParseErrorKind::Unsupported(
+                                "value orders other than all `a` or all `d`".to_owned(),
+                            ),
+                        });
+                    };
+                }
+                "MAXIMISING" | "MAXIMIZING" | "MINIMISING" | "MINIMIZING" => {
+                    return Err(ParseError {
+                        line,
+                        column,
+                        kind: ParseErrorKind::Unsupported("optimisation".to_owned()),
+                    })
+                }
+                _ => {
+                    return Err(ParseError {
+                        line,
+                        column,
+                        kind: ParseErrorKind::Syntax(format!(
+                            "expected a search statement, found `{statement}`"
+                        )),
+                    })
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    fn parse_constraints(&mut self) -> Result<()> {
+        while !self.at_section_end() {
+            let constraint = self.constraint()?;
+            self.model.constraints.push(constraint);
+        }
+
+        Ok(())
+    }
+
+    fn parse_tuple_lists(&mut self) -> Result<()> {
+        while !self.at_section_end() {
+            let name = self.ident()?;
+            let rows = self.int()?;
+            let (line, column) = self.position();
+            let arity = self.int()?;
+            if arity != 2 {
+                return Err(ParseError {
+                    line,
+                    column,
+                    kind: ParseErrorKind::Unsupported(format!("tuples of arity {arity}")),
+                });
+            }
+
+            let mut tuples = Vec::new();
+            for _ in 0..rows {
+                let a = self.int()?;
+                let b = self.int()?;
+                tuples.push((Constant::Integer(a), Constant::Integer(b)));
+            }
+
+            self.tuple_lists.insert(name, tuples);
+        }
+
+        Ok(())
+    }
+
+    /*****************************/
+    /*        Constraints        */
+    /*****************************/
+
+    fn constraint(&mut self) -> Result<Constraint> {
+        let (line, column) = self.position();
+        let mut name = self.ident()?;
+
+        // constraint variants such as check[assign] and lexleq[rv]
+        if self.peek() == Some('[') {
+            self.bump();
+            name.push('[');
+            name.push_str(&self.ident()?);
+            self.expect("]")?;
+            name.push(']');
+        }
+
+        // true and false can be given without brackets
+        if !self.at("(") {
+            match name.as_str() {
+                "true" => return Ok(Constraint::True),
+                "false" => return Ok(Constraint::False),
+                _ => (),
+            }
+        }
+
+        self.expect("(")?;
+
+        let constraint = match name.as_str() {
+            "difference" => {
+                let a = self.two_vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Difference(a, b)
+            }
+            "div" => {
+                let a = self.two_vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Div(a, b)
+            }
+            "div_undefzero" => {
+                let a = self.two_vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::DivUndefZero(a, b)
+            }
+            "modulo" => {
+                let a = self.two_vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Modulo(a, b)
+            }
+            "mod_undefzero" => {
+                let a = self.two_vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::ModuloUndefZero(a, b)
+            }
+            "pow" => {
+                let a = self.two_vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Pow(a, b)
+            }
+            "product" => {
+                let a = self.two_vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Product(a, b)
+            }
+            "weightedsumgeq" => {
+                let a = self.constants()?;
+                self.comma()?;
+                let b = self.vars()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::WeightedSumGeq(a, b, c)
+            }
+            "weightedsumleq" => {
+                let a = self.constants()?;
+                self.comma()?;
+                let b = self.vars()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::WeightedSumLeq(a, b, c)
+            }
+            "check[assign]" => Constraint::CheckAssign(Box::new(self.constraint()?)),
+            "check[gsa]" => Constraint::CheckGsa(Box::new(self.constraint()?)),
+            "forwardchecking" => Constraint::ForwardChecking(Box::new(self.constraint()?)),
+            "reify" => {
+                let a = Box::new(self.constraint()?);
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Reify(a, b)
+            }
+            "reifyimply" => {
+                let a = Box::new(self.constraint()?);
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::ReifyImply(a, b)
+            }
+            "reifyimply-quick" => {
+                let a = Box::new(self.constraint()?);
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::ReifyImplyQuick(a, b)
+            }
+            "watched-and" => Constraint::WatchedAnd(self.constraint_list()?),
+            "watched-or" => Constraint::WatchedOr(self.constraint_list()?),
+            "gacalldiff" => Constraint::GacAllDiff(self.vars()?),
+            "alldiff" => Constraint::AllDiff(self.vars()?),
+            "alldiffmatrix" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constant()?;
+                Constraint::AllDiffMatrix(a, b)
+            }
+            "watchsumgeq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constant()?;
+                Constraint::WatchSumGeq(a, b)
+            }
+            "watchsumleq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constant()?;
+                Constraint::WatchSumLeq(a, b)
+            }
+            "occurrencegeq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constant()?;
+                self.comma()?;
+                let c = self.constant()?;
+                Constraint::OccurrenceGeq(a, b, c)
+            }
+            "occurrenceleq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constant()?;
+                self.comma()?;
+                let c = self.constant()?;
+                Constraint::OccurrenceLeq(a, b, c)
+            }
+            "occurrence" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constant()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::Occurrence(a, b, c)
+            }
+            "litsumgeq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constants()?;
+                self.comma()?;
+                let c = self.constant()?;
+                Constraint::LitSumGeq(a, b, c)
+            }
+            "gcc" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constants()?;
+                self.comma()?;
+                let c = self.vars()?;
+                Constraint::Gcc(a, b, c)
+            }
+            "gccweak" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.constants()?;
+                self.comma()?;
+                let c = self.vars()?;
+                Constraint::GccWeak(a, b, c)
+            }
+            "lexleq[rv]" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                Constraint::LexLeqRv(a, b)
+            }
+            "lexleq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                Constraint::LexLeq(a, b)
+            }
+            "lexless" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                Constraint::LexLess(a, b)
+            }
+            "lexleq[quick]" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                Constraint::LexLeqQuick(a, b)
+            }
+            "lexless[quick]" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                Constraint::LexLessQuick(a, b)
+            }
+            "watchvecneq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                Constraint::WatchVecNeq(a, b)
+            }
+            "watchvecexists_less" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                Constraint::WatchVecExistsLess(a, b)
+            }
+            "hamming" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                self.comma()?;
+                let c = self.constant()?;
+                Constraint::Hamming(a, b, c)
+            }
+            "not-hamming" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                self.comma()?;
+                let c = self.constant()?;
+                Constraint::NotHamming(a, b, c)
+            }
+            "frameupdate" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.vars()?;
+                self.comma()?;
+                let c = self.vars()?;
+                self.comma()?;
+                let d = self.vars()?;
+                self.comma()?;
+                let e = self.constant()?;
+                Constraint::FrameUpdate(a, b, c, d, e)
+            }
+            "negativetable" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.tuples()?;
+                Constraint::NegativeTable(a, b)
+            }
+            "table" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.tuples()?;
+                Constraint::Table(a, b)
+            }
+            "gacschema" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.tuples()?;
+                Constraint::GacSchema(a, b)
+            }
+            "lighttable" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.tuples()?;
+                Constraint::LightTable(a, b)
+            }
+            "mddc" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.tuples()?;
+                Constraint::Mddc(a, b)
+            }
+            "negativemddc" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.tuples()?;
+                Constraint::NegativeMddc(a, b)
+            }
+            "str2plus" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Str2Plus(a, b)
+            }
+            "max" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Max(a, b)
+            }
+            "min" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Min(a, b)
+            }
+            "nvaluegeq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::NvalueGeq(a, b)
+            }
+            "nvalueleq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::NvalueLeq(a, b)
+            }
+            "sumleq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::SumLeq(a, b)
+            }
+            "sumgeq" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::SumGeq(a, b)
+            }
+            "element" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::Element(a, b, c)
+            }
+            "element_one" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::ElementOne(a, b, c)
+            }
+            "element_undefzero" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::ElementUndefZero(a, b, c)
+            }
+            "watchelement" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::WatchElement(a, b, c)
+            }
+            "watchelement_one" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::WatchElementOne(a, b, c)
+            }
+            "watchelement_one_undefzero" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::WatchElementOneUndefZero(a, b, c)
+            }
+            "watchelement_undefzero" => {
+                let a = self.vars()?;
+                self.comma()?;
+                let b = self.var()?;
+                self.comma()?;
+                let c = self.var()?;
+                Constraint::WatchElementUndefZero(a, b, c)
+            }
+            "w-literal" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.constant()?;
+                Constraint::WLiteral(a, b)
+            }
+            "w-notliteral" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.constant()?;
+                Constraint::WNotLiteral(a, b)
+            }
+            "w-inintervalset" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.constants()?;
+                Constraint::WInIntervalSet(a, b)
+            }
+            "w-inrange" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.constants()?;
+                Constraint::WInRange(a, b)
+            }
+            "w-inset" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.constants()?;
+                Constraint::WInset(a, b)
+            }
+            "w-notinrange" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.constants()?;
+                Constraint::WNotInRange(a, b)
+            }
+            "w-notinset" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.constants()?;
+                Constraint::WNotInset(a, b)
+            }
+            "abs" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Abs(a, b)
+            }
+            "diseq" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::DisEq(a, b)
+            }
+            "eq" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::Eq(a, b)
+            }
+            "minuseq" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::MinusEq(a, b)
+            }
+            "gaceq" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::GacEq(a, b)
+            }
+            "watchless" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::WatchLess(a, b)
+            }
+            "watchneq" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.var()?;
+                Constraint::WatchNeq(a, b)
+            }
+            "ineq" => {
+                let a = self.var()?;
+                self.comma()?;
+                let b = self.var()?;
+                self.comma()?;
+                let c = self.constant()?;
+                Constraint::Ineq(a, b, c)
+            }
+            "true" => Constraint::True,
+            "false" => Constraint::False,
+            _ => {
+                return Err(ParseError {
+                    line,
+                    column,
+                    kind: ParseErrorKind::UnsupportedConstraint(name),
+                })
+            }
+        };
+
+        self.expect(")")?;
+        Ok(constraint)
+    }
+
+    fn constraint_list(&mut self) -> Result<Vec<Constraint>> {
+        self.expect("{")?;
+        self.comma_separated("}", Self::constraint)
+    }
+
+    fn tuples(&mut self) -> Result<Vec<Tuple>> {
+        if self.eat("{") {
+            return self.comma_separated("}", Self::tuple);
+        }
+
+        let (line, column) = self.position();
+        let name = self.ident()?;
+        match self.tuple_lists.get(&name) {
+            Some(tuples) => Ok(tuples.clone()),
+            None => Err(ParseError {
+                line,
+                column,
+                kind: ParseErrorKind::Syntax(format!("undefined tuple list `{name}`")),
+            }),
+        }
+    }
+
+    fn tuple(&mut self) -> Result<Tuple> {
+        let (line, column) = self.position();
+        self.expect("<")?;
+        let values = self.comma_separated(">", Self::int)?;
+        match values[..] {
+            [a, b] => Ok((Constant::Integer(a), Constant::Integer(b))),
+            _ => Err(ParseError {
+                line,
+                column,
+                kind: ParseErrorKind::Unsupported(format!("tuples of arity {}", values.len())),
+            }),
+        }
+    }
+
+    /*****************************/
+    /*        Arguments          */
+    /*****************************/
+
+    fn var(&mut self) -> Result<Var> {
+        self.skip_whitespace();
+        if matches!(self.peek(), Some(c) if c == '-' || c.is_ascii_digit()) {
+            return Ok(Var::ConstantAsVar(self.int()?));
+        }
+
+        Ok(Var::NameRef(self.named_var()?))
+    }
+
+    /// Parses a reference to a declared variable.
+    fn named_var(&mut self) -> Result<VarName> {
+        let (line, column) = self.position();
+        let name = self.var_name()?;
+        if !self.model.named_variables.contains(name.clone()) {
+            return Err(ParseError {
+                line,
+                column,
+                kind: ParseErrorKind::UnknownVariable(name),
+            });
+        }
+
+        Ok(name)
+    }
+
+    fn two_vars(&mut self) -> Result<(Var, Var)> {
+        let a = self.var()?;
+        self.comma()?;
+        let b = self.var()?;
+        Ok((a, b))
+    }
+
+    fn vars(&mut self) -> Result<Vec<Var>> {
+        self.expect("[")?;
+        self.comma_separated("]", Self::var)
+    }
+
+    /// Parses a possibly nested list of variables, as used by `PRINT`.
+    fn nested_var_list(&mut self) -> Result<Vec<VarName>> {
+        self.expect("[")?;
+        let lists = self.comma_separated("]", |parser| {
+            if parser.at("[") {
+                parser.nested_var_list()
+            } else {
+                Ok(vec![parser.named_var()?])
+            }
+        })?;
+
+        Ok(lists.into_iter().flatten().collect())
+    }
+
+    fn constant(&mut self) -> Result<Constant> {
+        Ok(Constant::Integer(self.int()?))
+    }
+
+    fn constants(&mut self) -> Result<Vec<Constant>> {
+        self.expect("[")?;
+        self.comma_separated("]", Self::constant)
+    }
+
+    fn var_name(&mut self) -> Result<VarName> {
+        let name = self.ident()?;
+        if self.peek() == Some('[') {
+            return self.error(ParseErrorKind::Unsupported("matrix variables".to_owned()));
+        }
+        Ok(name)
+    }
+
+    fn range(&mut self) -> Result<(i32, i32)> {
+        self.expect("{")?;
+        let lo = self.int()?;
+        self.expect("..")?;
+        let hi = self.int()?;
+        self.expect("}")?;
+        Ok((lo, hi))
+    }
+
+    /*****************************/
+    /*        Tokens             */
+    /*****************************/
+
+    /// Parses items separated by commas, up to and including the `close` delimiter.
+    fn comma_separated<T>(
+        &mut self,
+        close: &str,
+        mut item: impl FnMut(&mut Self) -> Result<T>,
+    ) -> Result<Vec<T>> {
+        let mut items = Vec::new();
+        if self.eat(close) {
+            return Ok(items);
+        }
+
+        loop {
+            items.push(item(self)?);
+            if !self.eat(",") {
+                break;
+            }
+        }
+
+        self.expect(close)?;
+        Ok(items)
+    }
+
+    fn comma(&mut self) -> Result<()> {
+        self.expect(",")
+    }
+
+    fn ident(&mut self) -> Result<String> {
+        self.skip_whitespace();
+        match self.peek() {
+            Some(c) if c.is_alphabetic() || c == '_' => (),
+            _ => return self.error(ParseErrorKind::Syntax("expected an identifier".to_owned())),
+        }
+
+        let mut ident = String::new();
+        while let Some(c) = self.peek() {
+            if !(c.is_alphanumeric() || c == '_' || c == '-') {
+                break;
+            }
+            ident.push(c);
+            self.bump();
+        }
+
+        Ok(ident)
+    }
+
+    fn int(&mut self) -> Result<i32> {
+        let (line, column) = self.position();
+        let mut digits = String::new();
+        if self.peek() == Some('-') {
+            digits.push('-');
+            self.bump();
+        }
+
+        while let Some(c) = self.peek() {
+            if !c.is_ascii_digit() {
+                break;
+            }
+            digits.push(c);
+            self.bump();
+        }
+
+        digits.parse().map_err(|_| ParseError {
+            line,
+            column,
+            kind: ParseErrorKind::Syntax("expected an integer".to_owned()),
+        })
+    }
+
+    /// Consumes `token` if it is next in the input.
+    fn eat(&mut self, token: &str) -> bool {
+        if !self.at(token) {
+            return false;
+        }
+
+        for _ in token.chars() {
+            self.bump();
+        }
+        true
+    }
+
+    fn expect(&mut self, token: &str) -> Result<()> {
+        if self.eat(token) {
+            return Ok(());
+        }
+
+        let found = match self.peek() {
+            Some(c) => format!("`{c}`"),
+            None => "end of file".to_owned(),
+        };
+        self.error(ParseErrorKind::Syntax(format!(
+            "expected `{token}`, found {found}"
+        )))
+    }
+
+    /// Checks if `token` is next in the input, without consuming it.
+    fn at(&mut self, token: &str) -> bool {
+        self.skip_whitespace();
+        token
+            .chars()
+            .enumerate()
+            .all(|(i, c)| self.chars.get(self.pos + i) == Some(&c))
+    }
+
+    fn at_section_end(&mut self) -> bool {
+        self.at("**") || self.peek().is_none()
+    }
+
+    /// Skips whitespace and `#` comments.
+    fn skip_whitespace(&mut self) {
+        while let Some(c) = self.peek() {
+            if c == '#' {
+                while !matches!(self.peek(), Some('\n') | None) {
+                    self.bump();
+                }
+            } else if c.is_whitespace() {
+                self.bump();
+            } else {
+                break;
+            }
+        }
+    }
+
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.pos).copied()
+    }
+
+    fn bump(&mut self) -> Option<char> {
+        let c = self.peek()?;
+        self.pos += 1;
+        if c == '\n' {
+            self.line += 1;
+            self.column = 1;
+        } else {
+            self.column += 1;
+        }
+        Some(c)
+    }
+
+    /// The position of the next item in the input.
+    fn position(&mut self) -> (usize, usize) {
+        self.skip_whitespace();
+        (self.line, self.column)
+    }
+
+    fn error<T>(&mut self, kind: ParseErrorKind) -> Result<T> {
+        let (line, column) = self.position();
+        Err(ParseError { line, column, kind })
+    }
+}
@@ -0,0 +1,286 @@
+//! Writing [Model]s in Minion's textual input format.
+//!
+//! The output of [write_minion_file] is a valid `MINION 3` input file, which can be given to the
+//! standalone Minion binary to reproduce the behaviour of [run_minion](crate::run_minion).
+
+use std::io::Write;
+
+use crate::ast::{
+    Constant, Constraint, Model, SearchOrder, Tuple, ValueOrder, Var, VarDomain, VarName, VarOrder,
+};
+
+/// Writes the given [Model] to `buf` as a `MINION 3` input file.
+///
+/// The `PRINT` and `VARORDER` statements are taken from [Model::print_vars] and
+/// [Model::search_orders]. Variables not in any search order are written to a `VARORDER AUX`
+/// statement, matching the behaviour of [run_minion](crate::run_minion).
+///
+/// # Examples
+///
+/// ```
+/// use minion_rs::ast::*;
+/// use minion_rs::print::write_minion_file;
+///
+/// let mut model = Model::new();
+/// model
+///     .named_variables
+///     .add_var("x".to_owned(), VarDomain::Bound(1, 3));
+/// model
+///     .named_variables
+///     .add_var("y".to_owned(), VarDomain::Bool);
+///
+/// model.constraints.push(Constraint::Ineq(
+///     Var::NameRef("x".to_owned()),
+///     Var::ConstantAsVar(2),
+///     Constant::Integer(0),
+/// ));
+///
+/// let mut buf: Vec<u8> = Vec::new();
+/// write_minion_file(&mut buf, &model).unwrap();
+///
+/// assert_eq!(
+///     String::from_utf8(buf).unwrap(),
+///     "MINION 3\n\
+///      **VARIABLES**\n\
+///      BOUND x {1..3}\n\
+///      BOOL y\n\
+///      **SEARCH**\n\
+///      PRINT [[x],[y]]\n\
+///      VARORDER STATIC [x,y]\n\
+///      **CONSTRAINTS**\n\
+///      ineq(x,2,0)\n\
+///      **EOF**\n"
+/// );
+/// ```
+pub fn write_minion_file(buf: &mut impl Write, model: &Model) -> Result<(), std::io::Error> {
+    let var_order = model.named_variables.get_variable_order();
+
+    writeln!(buf, "MINION 3")?;
+
+    writeln!(buf, "**VARIABLES**")?;
+    for var_name in &var_order {
+        // the variable order only contains names from the symbol table.
+        #[allow(clippy::unwrap_used)]
+        let domain = model.named_variables.get_vartype(var_name.clone()).unwrap();
+        writeln!(buf, "{}", fmt_var_declaration(var_name, &domain))?;
+    }
+
+    writeln!(buf, "**SEARCH**")?;
+    let print_vars = model.print_vars.as_ref().unwrap_or(&var_order);
+    writeln!(
+        buf,
+        "PRINT [{}]",
+        print_vars
+            .iter()
+            .map(|x| format!("[{}]", x))
+            .collect::<Vec<_>>()
+            .join(",")
+    )?;
+
+    if model.search_orders.is_empty() {
+        writeln!(buf, "VARORDER STATIC [{}]", var_order.join(","))?;
+    } else {
+        for search_order in &model.search_orders {
+            writeln!(buf, "{}", fmt_search_order(search_order))?;
+        }
+
+        let aux_vars: Vec<VarName> = var_order
+            .iter()
+            .filter(|x| !model.search_orders.iter().any(|o| o.vars.contains(x)))
+            .cloned()
+            .collect();
+        if !aux_vars.is_empty() {
+            writeln!(buf, "VARORDER AUX [{}]", aux_vars.join(","))?;
+        }
+    }
+
+    writeln!(buf, "**CONSTRAINTS**")?;
+    for constraint in &model.constraints {
+        writeln!(buf, "{}", fmt_constraint(constraint))?;
+    }
+
+    writeln!(buf, "**EOF**")?;
+    Ok(())
+}
+
+fn fmt_var_declaration(name: &str, domain: &VarDomain) -> String {
+    match domain {
+        VarDomain::Bound(low, high) => format!("BOUND {} {{{}..{}}}", name, low, high),
+        VarDomain::Discrete(low, high) => format!("DISCRETE {} {{{}..{}}}", name, low, high),
+        VarDomain::SparseBound(a, b) => format!("SPARSEBOUND {} {{{},{}}}", name, a, b),
+        VarDomain::Bool => format!("BOOL {}", name),
+    }
+}
+
+fn fmt_search_order(search_order: &SearchOrder) -> String {
+    let var_order = match search_order.var_order {
+        VarOrder::Static => "STATIC",
+        VarOrder::Sdf => "SDF",
+        VarOrder::Conflict => "CONFLICT",
+    };
+
+    let mut out = format!("VARORDER {} [{}]", var_order, search_order.vars.join(","));
+    if search_order.value_order == ValueOrder::Descending {
+        let value_orders = vec!["d"; search_order.vars.len()];
+        out.push_str(&format!("\nVALORDER [{}]", value_orders.join(",")));
+    }
+    out
+}
+
+/// Formats a single constraint as it would appear in the **CONSTRAINTS** section of a Minion
+/// file.
+pub fn fmt_constraint(constraint: &Constraint) -> String {
+    match constraint {
+        Constraint::Difference((a, b), c) => fmt_call("difference", &[fmt_2_vars(a, b), v(c)]),
+        Constraint::Div((a, b), c) => fmt_call("div", &[fmt_2_vars(a, b), v(c)]),
+        Constraint::DivUndefZero((a, b), c) => fmt_call("div_undefzero", &[fmt_2_vars(a, b), v(c)]),
+        Constraint::Modulo((a, b), c) => fmt_call("modulo", &[fmt_2_vars(a, b), v(c)]),
+        Constraint::ModuloUndefZero((a, b), c) => {
+            fmt_call("mod_undefzero", &[fmt_2_vars(a, b), v(c)])
+        }
+        Constraint::Pow((a, b), c) => fmt_call("pow", &[fmt_2_vars(a, b), v(c)]),
+        Constraint::Product((a, b), c) => fmt_call("product", &[fmt_2_vars(a, b), v(c)]),
+        Constraint::WeightedSumGeq(a, b, c) => {
+            fmt_call("weightedsumgeq", &[consts(a), vars(b), v(c)])
+        }
+        Constraint::WeightedSumLeq(a, b, c) => {
+            fmt_call("weightedsumleq", &[consts(a), vars(b), v(c)])
+        }
+        Constraint::CheckAssign(a) => fmt_call("check[assign]", &[fmt_constraint(a)]),
+        Constraint::CheckGsa(a) => fmt_call("check[gsa]", &[fmt_constraint(a)]),
+        Constraint::ForwardChecking(a) => fmt_call("forwardchecking", &[fmt_constraint(a)]),
+        Constraint::Reify(a, b) => fmt_call("reify", &[fmt_constraint(a), v(b)]),
+        Constraint::ReifyImply(a, b) => fmt_call("reifyimply", &[fmt_constraint(a), v(b)]),
+        Constraint::ReifyImplyQuick(a, b) => {
+            fmt_call("reifyimply-quick", &[fmt_constraint(a), v(b)])
+        }
+        Constraint::WatchedAnd(a) => fmt_call("watched-and", &[constraints(a)]),
+        Constraint::WatchedOr(a) => fmt_call("watched-or", &[constraints(a)]),
+        Constraint::GacAllDiff(a) => fmt_call("gacalldiff", &[vars(a)]),
+        Constraint::AllDiff(a) => fmt_call("alldiff", &[vars(a)]),
+        Constraint::AllDiffMatrix(a, b) => fmt_call("alldiffmatrix", &[vars(a), c(b)]),
+        Constraint::WatchSumGeq(a, b) => fmt_call("watchsumgeq", &[vars(a), c(b)]),
+        Constraint::WatchSumLeq(a, b) => fmt_call("watchsumleq", &[vars(a), c(b)]),
+        Constraint::OccurrenceGeq(a, b, d) => fmt_call("occurrencegeq", &[vars(a), c(b), c(d)]),
+        Constraint::OccurrenceLeq(a, b, d) => fmt_call("occurrenceleq", &[vars(a), c(b), c(d)]),
+        Constraint::Occurrence(a, b, d) => fmt_call("occurrence", &[vars(a), c(b), v(d)]),
+        Constraint::LitSumGeq(a, b, d) => fmt_call("litsumgeq", &[vars(a), consts(b), c(d)]),
+        Constraint::Gcc(a, b, d) => fmt_call("gcc", &[vars(a), consts(b), vars(d)]),
+        Constraint::GccWeak(a, b, d) => fmt_call("gccweak", &[vars(a), consts(b), vars(d)]),
+        Constraint::LexLeqRv(a, b) => fmt_call("lexleq[rv]", &[vars(a), vars(b)]),
+        Constraint::LexLeq(a, b) => fmt_call("lexleq", &[vars(a), vars(b)]),
+        Constraint::LexLess(a, b) => fmt_call("lexless", &[vars(a), vars(b)]),
+        Constraint::LexLeqQuick(a, b) => fmt_call("lexleq[quick]", &[vars(a), vars(b)]),
+        Constraint::LexLessQuick(a, b) => fmt_call("lexless[quick]", &[vars(a), vars(b)]),
+        Constraint::WatchVecNeq(a, b) => fmt_call("watchvecneq", &[vars(a), vars(b)]),
+        Constraint::WatchVecExistsLess(a, b) => {
+            fmt_call("watchvecexists_less", &[vars(a), vars(b)])
+        }
+        Constraint::Hamming(a, b, d) => fmt_call("hamming", &[vars(a), vars(b), c(d)]),
+        Constraint::NotHamming(a, b, d) => fmt_call("not-hamming", &[vars(a), vars(b), c(d)]),
+        Constraint::FrameUpdate(a, b, d, e, f) => {
+            fmt_call("frameupdate", &[vars(a), vars(b), vars(d), vars(e), c(f)])
+        }
+        Constraint::NegativeTable(a, b) => fmt_call("negativetable", &[vars(a), tuples(b)]),
+        Constraint::Table(a, b) => fmt_call("table", &[vars(a), tuples(b)]),
+        Constraint::GacSchema(a, b) => fmt_call("gacschema", &[vars(a), tuples(b)]),
+        Constraint::LightTable(a, b) => fmt_call("lighttable", &[vars(a), tuples(b)]),
+        Constraint::Mddc(a, b) => fmt_call("mddc", &[vars(a), tuples(b)]),
+        Constraint::NegativeMddc(a, b) => fmt_call("negativemddc", &[vars(a), tuples(b)]),
+        Constraint::Str2Plus(a, b) => fmt_call("str2plus", &[vars(a), v(b)]),
+        Constraint::Max(a, b) => fmt_call("max", &[vars(a), v(b)]),
+        Constraint::Min(a, b) => fmt_call("min", &[vars(a), v(b)]),
+        Constraint::NvalueGeq(a, b) => fmt_call("nvaluegeq", &[vars(a), v(b)]),
+        Constraint::NvalueLeq(a, b) => fmt_call("nvalueleq", &[vars(a), v(b)]),
+        Constraint::SumLeq(a, b) => fmt_call("sumleq", &[vars(a), v(b)]),
+        Constraint::SumGeq(a, b) => fmt_call("sumgeq", &[vars(a), v(b)]),
+        Constraint::Element(a, b, d) => fmt_call("element", &[vars(a), v(b), v(d)]),
+        Constraint::ElementOne(a, b, d) => fmt_call("element_one", &[vars(a), v(b), v(d)]),
+        Constraint::ElementUndefZero(a, b, d) => {
+            fmt_call("element_undefzero", &[vars(a), v(b), v(d)])
+        }
+        Constraint::WatchElement(a, b, d) => fmt_call("watchelement", &[vars(a), v(b), v(d)]),
+        Constraint::WatchElementOne(a, b, d) => {
+            fmt_call("watchelement_one", &[vars(a), v(b), v(d)])
+        }
+        Constraint::WatchElementOneUndefZero(a, b, d) => {
+            fmt_call("watchelement_one_undefzero", &[vars(a), v(b), v(d)])
+        }
+        Constraint::WatchElementUndefZero(a, b, d) => {
+            fmt_call("watchelement_undefzero", &[vars(a), v(b), v(d)])
+        }
+        Constraint::WLiteral(a, b) => fmt_call("w-literal", &[v(a), c(b)]),
+        Constraint::WNotLiteral(a, b) => fmt_call("w-notliteral", &[v(a), c(b)]),
+        Constraint::WInIntervalSet(a, b) => fmt_call("w-inintervalset", &[v(a), consts(b)]),
+        Constraint::WInRange(a, b) => fmt_call("w-inrange", &[v(a), consts(b)]),
+        Constraint::WInset(a, b) => fmt_call("w-inset", &[v(a), consts(b)]),
+        Constraint::WNotInRange(a, b) => fmt_call("w-notinrange", &[v(a), consts(b)]),
+        Constraint::WNotInset(a, b) => fmt_call("w-notinset", &[v(a), consts(b)]),
+        Constraint::Abs(a, b) => fmt_call("abs", &[v(a), v(b)]),
+        Constraint::DisEq(a, b) => fmt_call("diseq", &[v(a), v(b)]),
+        Constraint::Eq(a, b) => fmt_call("eq", &[v(a), v(b)]),
+        Constraint::MinusEq(a, b) => fmt_call("minuseq", &[v(a), v(b)]),
+        Constraint::GacEq(a, b) => fmt_call("gaceq", &[v(a), v(b)]),
+        Constraint::WatchLess(a, b) => fmt_call("watchless", &[v(a), v(b)]),
+        Constraint::WatchNeq(a, b) => fmt_call("watchneq", &[v(a), v(b)]),
+        Constraint::Ineq(a, b, d) => fmt_call("ineq", &[v(a), v(b), c(d)]),
+        Constraint::False => "false()".to_owned(),
+        Constraint::True => "true()".to_owned(),
+    }
+}
+
+fn fmt_call(name: &str, args: &[String]) -> String {
+    format!("{}({})", name, args.join(","))
+}
+
+fn fmt_2_vars(a: &Var, b: &Var) -> String {
+    format!("{},{}", v(a), v(b))
+}
+
+fn v(var: &Var) -> String {
+    match var {
+        Var::NameRef(name) => name.clone(),
+        Var::ConstantAsVar(n) => n.to_string(),
+    }
+}
+
+fn vars(vars: &[Var]) -> String {
+    format!("[{}]", vars.iter().map(v).collect::<Vec<_>>().join(","))
+}
+
+fn c(constant: &Constant) -> String {
+    match constant {
+        Constant::Bool(true) => "1".to_owned(),
+        Constant::Bool(false) => "0".to_owned(),
+        Constant::Integer(n) => n.to_string(),
+    }
+}
+
+fn consts(constants: &[Constant]) -> String {
+    format!(
+        "[{}]",
+        constants.iter().map(c).collect::<Vec<_>>().join(",")
+    )
+}
+
+fn constraints(constraints: &[Constraint]) -> String {
+    format!(
+        "{{{}}}",
+        constraints
+            .iter()
+            .map(fmt_constraint)
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
+
+fn tuples(tuples: &[Tuple]) -> String {
+    format!(
+        "{{{}}}",
+        tuples
+            .iter()
+            .map(|(a, b)| format!("<{},{}>", c(a), c(b)))
+            .collect::<Vec<_>>()
+            .join(",")
+    )
+}
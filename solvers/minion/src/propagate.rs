@@ -0,0 +1,111 @@
+//! Using Minion's propagation to filter the domains of variables.
+
+use std::collections::BTreeMap;
+
+use crate::ast::{Model, VarName};
+use crate::error::{MinionError, RuntimeError};
+use crate::ffi;
+use crate::run::{convert_model_to_raw, get_named_var, with_lock};
+use crate::scoped_ptr::Scoped;
+use crate::wrappers::get_last_error;
+use crate::SearchOptions;
+
+/// The result of [propagate_root].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RootPropagation {
+    /// Propagation succeeded, leaving these values in the domain of each variable, in ascending
+    /// order.
+    Domains(BTreeMap<VarName, Vec<i32>>),
+
+    /// Propagation at the root node failed, so the model has no solutions.
+    Failed,
+}
+
+/// Runs Minion's propagation at the root node on the given [Model], without any search, and
+/// returns the filtered domain of every variable.
+///
+/// This is the propagation Minion does before it starts search, so values are only removed if
+/// Minion proves that they have no support: no solutions are lost.
+///
+/// # Examples
+///
+/// ```
+/// use minion_rs::ast::*;
+/// use minion_rs::propagate::{propagate_root, RootPropagation};
+///
+/// // x < y, x and y in 1..3.
+/// let mut model = Model::new();
+/// model
+///     .named_variables
+///     .add_var("x".to_owned(), VarDomain::Bound(1, 3));
+/// model
+///     .named_variables
+///     .add_var("y".to_owned(), VarDomain::Bound(1, 3));
+/// model.constraints.push(Constraint::Ineq(
+///     Var::NameRef("x".to_owned()),
+///     Var::NameRef("y".to_owned()),
+///     Constant::Integer(-1),
+/// ));
+///
+/// let RootPropagation::Domains(domains) = propagate_root(&model).unwrap() else {
+///     panic!("expected propagation to succeed");
+/// };
+///
+/// assert_eq!(domains["x"], vec![1, 2]);
+/// assert_eq!(domains["y"], vec![2, 3]);
+/// ```
+pub fn propagate_root(model: &Model) -> Result<RootPropagation, MinionError> {
+    with_lock(|| unsafe { propagate_root_locked(model) })
+}
+
+/// Runs root propagation. The caller must hold the lock taken by [with_lock].
+unsafe fn propagate_root_locked(model: &Model) -> Result<RootPropagation, MinionError> {
+    let search_opts = Scoped::new(ffi::searchOptions_new(), |x| {
+        ffi::searchOptions_free(x as _)
+    });
+    let search_method = Scoped::new(ffi::searchMethod_new(), |x| ffi::searchMethod_free(x as _));
+    let instance = Scoped::new(ffi::instance_new(), |x| ffi::instance_free(x as _));
+
+    SearchOptions::default().apply(search_opts.ptr, search_method.ptr);
+    convert_model_to_raw(instance.ptr, model)?;
+
+    let names = model.named_variables.get_variable_order();
+    let vars = Scoped::new(ffi::vec_var_new(), |x| ffi::vec_var_free(x as _));
+    for name in &names {
+        let var = get_named_var(instance.ptr, model, name)?;
+        ffi::vec_var_push_back(vars.ptr, var);
+    }
+
+    let domains = Scoped::new(ffi::vec_vec_int_new(), |x| ffi::vec_vec_int_free(x as _));
+    let mut failed = false;
+    let res = ffi::instance_propagateRoot(
+        search_opts.ptr,
+        search_method.ptr,
+        instance.ptr,
+        vars.ptr,
+        domains.ptr,
+        &mut failed,
+    );
+
+    if res != 0 {
+        return Err(RuntimeError::from_return_code(res, get_last_error()).into());
+    }
+
+    if failed {
+        return Ok(RootPropagation::Failed);
+    }
+
+    let domains = names
+        .into_iter()
+        .enumerate()
+        .map(|(i, name)| {
+            let domain = ffi::vec_vec_int_at(domains.ptr, i);
+            let values = (0..ffi::vec_int_size(domain))
+                .map(|j| ffi::vec_int_at(domain, j))
+                .collect();
+            (name, values)
+        })
+        .collect();
+
+    Ok(RootPropagation::Domains(domains))
+}
@@ -2,47 +2,69 @@
 
 use std::{
     collections::HashMap,
-    ffi::CString,
-    sync::Condvar,
-    sync::{Mutex, MutexGuard},
+    ffi::{c_void, CString},
+    sync::{Condvar, Mutex},
 };
 
 use anyhow::anyhow;
 
 use crate::ffi::{self};
-use crate::{ast::*, error::*, scoped_ptr::Scoped};
+use crate::{
+    ast::*, error::*, options::SearchOptions, scoped_ptr::Scoped, wrappers::get_last_error,
+};
+
+/// The state of a single run of Minion, shared between [run_minion_with_options] and
+/// [run_callback].
+struct RunState<'a> {
+    /// The user's callback.
+    callback: &'a mut dyn FnMut(HashMap<VarName, Constant>) -> bool,
+
+    /// The variables we want to return, in the order they appear in the print matrix.
+    print_vars: Vec<VarName>,
+}
+
+static LOCK: (Mutex<bool>, Condvar) = (Mutex::new(false), Condvar::new());
+
+/// Minion's callback for a run, given a pointer to the [RunState] of that run as its user data.
+unsafe extern "C" fn run_callback(user_data: *mut c_void) -> bool {
+    // SAFETY: user_data is the RunState given to runMinionKeepingErrors by run_minion_locked,
+    // which outlives the run.
+    let state = &mut *user_data.cast::<RunState<'_>>();
 
-/// The callback function used to capture results from Minion as they are generated.
+    // build nice solutions view to be used by callback
+    let mut solutions: HashMap<VarName, Constant> = HashMap::new();
+
+    for (i, var) in state.print_vars.iter().enumerate() {
+        let solution_int: i32 = ffi::printMatrix_getValue(i as _);
+        let solution: Constant = Constant::Integer(solution_int);
+        solutions.insert(var.to_string(), solution);
+    }
+
+    (state.callback)(solutions)
+}
+
+/// Run Minion on the given [Model].
 ///
-/// This function is called by Minion whenever a solution is found. The input to this function is
-/// a`HashMap` of all named variables along with their value.
+/// The given callback is ran whenever a new solution set is found. Its input is a `HashMap` of
+/// the model's [print variables](Model::print_vars) along with their value.
 ///
 /// Callbacks should return `true` if search is to continue, `false` otherwise.
 ///
 /// # Examples
 ///
-/// Consider using a global mutex (or other static variable) to use returned solutions elsewhere.
+/// Callbacks are closures, so can capture local state to use returned solutions elsewhere.
 ///
 /// For example:
 ///
 /// ```
 ///   use minion_rs::ast::*;
 ///   use minion_rs::run_minion;
-///   use std::{
-///       collections::HashMap,
-///       sync::{Mutex, MutexGuard},
-///   };
+///   use std::collections::HashMap;
 ///
 ///   // More elaborate data-structures are possible, but for sake of example store
 ///   // a vector of solution sets.
-///   static ALL_SOLUTIONS: Mutex<Vec<HashMap<VarName,Constant>>>  = Mutex::new(vec![]);
-///   
-///   fn callback(solutions: HashMap<VarName,Constant>) -> bool {
-///       let mut guard = ALL_SOLUTIONS.lock().unwrap();
-///       guard.push(solutions);
-///       true
-///   }
-///    
+///   let mut all_solutions: Vec<HashMap<VarName, Constant>> = vec![];
+///
 ///   // Build and run the model.
 ///   let mut model = Model::new();
 ///
@@ -84,127 +106,120 @@ use crate::{ast::*, error::*, scoped_ptr::Scoped};
 /// # model.constraints.push(leq);
 /// # model.constraints.push(geq);
 /// # model.constraints.push(ineq);
-///  
-///   let res = run_minion(model, callback);
+///
+///   let res = run_minion(model, |solutions| {
+///       all_solutions.push(solutions);
+///       true
+///   });
 ///   res.expect("Error occurred");
 ///
 ///   // Get solutions
-///   let guard = ALL_SOLUTIONS.lock().unwrap();
-///   let solution_set_1 = &(guard.get(0).unwrap());
+///   let solution_set_1 = &(all_solutions.get(0).unwrap());
 ///
 ///   let x1 = solution_set_1.get("x").unwrap();
 ///   let y1 = solution_set_1.get("y").unwrap();
 ///   let z1 = solution_set_1.get("z").unwrap();
 /// #
 /// # // TODO: this test would be better with an example with >1 solution.
-/// # assert_eq!(guard.len(),1);
+/// # assert_eq!(all_solutions.len(),1);
 /// # assert_eq!(*x1,Constant::Integer(1));
 /// # assert_eq!(*y1,Constant::Integer(2));
 /// # assert_eq!(*z1,Constant::Integer(1));
 /// ```
-pub type Callback = fn(solution_set: HashMap<VarName, Constant>) -> bool;
-
-// Use globals to pass things between run_minion and the callback function.
-// Minion is (currently) single threaded anyways so the Mutexs' don't matter.
-
-// the current callback function
-static CALLBACK: Mutex<Option<Callback>> = Mutex::new(None);
-
-// the variables we want to return, and their ordering in the print matrix
-static PRINT_VARS: Mutex<Option<Vec<VarName>>> = Mutex::new(None);
-
-static LOCK: (Mutex<bool>, Condvar) = (Mutex::new(false), Condvar::new());
-
-#[no_mangle]
-unsafe extern "C" fn run_callback() -> bool {
-    // get printvars from static PRINT_VARS if they exist.
-    // if not, return true and continue search.
-
-    // Mutex poisoning is probably panic worthy.
-    #[allow(clippy::unwrap_used)]
-    let mut guard: MutexGuard<'_, Option<Vec<VarName>>> = PRINT_VARS.lock().unwrap();
-
-    if guard.is_none() {
-        return true;
-    }
-
-    let print_vars = match &mut *guard {
-        Some(x) => x,
-        None => unreachable!(),
-    };
-
-    if print_vars.is_empty() {
-        return true;
-    }
-
-    // build nice solutions view to be used by callback
-    let mut solutions: HashMap<VarName, Constant> = HashMap::new();
-
-    for (i, var) in print_vars.iter().enumerate() {
-        let solution_int: i32 = ffi::printMatrix_getValue(i as _);
-        let solution: Constant = Constant::Integer(solution_int);
-        solutions.insert(var.to_string(), solution);
-    }
-
-    #[allow(clippy::unwrap_used)]
-    match *CALLBACK.lock().unwrap() {
-        None => true,
-        Some(func) => func(solutions),
-    }
+pub fn run_minion<F>(model: Model, callback: F) -> Result<(), MinionError>
+where
+    F: FnMut(HashMap<VarName, Constant>) -> bool,
+{
+    run_minion_with_options(model, &SearchOptions::default(), callback)
 }
 
-/// Run Minion on the given [Model].
+/// Run Minion on the given [Model], using the given [SearchOptions].
 ///
-/// The given [callback](Callback) is ran whenever a new solution set is found.
+/// The given callback is ran whenever a new solution set is found. See [run_minion] for details.
+pub fn run_minion_with_options<F>(
+    model: Model,
+    options: &SearchOptions,
+    mut callback: F,
+) -> Result<(), MinionError>
+where
+    F: FnMut(HashMap<VarName, Constant>) -> bool,
+{
+    with_lock(|| unsafe { run_minion_locked(&model, options, &mut callback) })
+}
 
-// Turn it into a warning for this function, cant unwarn it directly above callback wierdness
+/// Runs `f` while holding [LOCK], so that no other run of Minion happens at the same time.
 #[allow(clippy::unwrap_used)]
-pub fn run_minion(model: Model, callback: Callback) -> Result<(), MinionError> {
+pub(crate) fn with_lock<T>(f: impl FnOnce() -> T) -> T {
     // Mutex poisoning is probably panic worthy.
-    *CALLBACK.lock().unwrap() = Some(callback);
-
     let (lock, condvar) = &LOCK;
-    let mut _lock_guard = condvar
+    let mut lock_guard = condvar
         .wait_while(lock.lock().unwrap(), |locked| *locked)
         .unwrap();
 
-    *_lock_guard = true;
+    *lock_guard = true;
 
-    unsafe {
-        // TODO: something better than a manual spinlock
-        let search_opts = ffi::searchOptions_new();
-        let search_method = ffi::searchMethod_new();
-        let search_instance = ffi::instance_new();
+    let res = f();
 
-        convert_model_to_raw(search_instance, &model)?;
+    *lock_guard = false;
+    std::mem::drop(lock_guard);
 
-        let res = ffi::runMinion(
-            search_opts,
-            search_method,
-            search_instance,
-            Some(run_callback),
-        );
+    condvar.notify_one();
 
-        ffi::searchMethod_free(search_method);
-        ffi::searchOptions_free(search_opts);
-        ffi::instance_free(search_instance);
+    res
+}
 
-        *_lock_guard = false;
-        std::mem::drop(_lock_guard);
+/// Runs Minion. The caller must hold [LOCK].
+unsafe fn run_minion_locked(
+    model: &Model,
+    options: &SearchOptions,
+    callback: &mut dyn FnMut(HashMap<VarName, Constant>) -> bool,
+) -> Result<(), MinionError> {
+    let search_opts = ffi::searchOptions_new();
+    let search_method = ffi::searchMethod_new();
+    let search_instance = ffi::instance_new();
+
+    options.apply(search_opts, search_method);
+
+    let res = match convert_model_to_raw(search_instance, model) {
+        Ok(print_vars) => {
+            let mut state = RunState {
+                callback,
+                print_vars,
+            };
+
+            let state_ptr: *mut RunState<'_> = &mut state;
+
+            let res = ffi::runMinionKeepingErrors(
+                search_opts,
+                search_method,
+                search_instance,
+                Some(run_callback),
+                state_ptr.cast(),
+            );
+
+            match res {
+                0 => Ok(()),
+                x => Err(MinionError::from(RuntimeError::from_return_code(
+                    x,
+                    get_last_error(),
+                ))),
+            }
+        }
+        Err(err) => Err(err),
+    };
 
-        condvar.notify_one();
+    ffi::searchMethod_free(search_method);
+    ffi::searchOptions_free(search_opts);
+    ffi::instance_free(search_instance);
 
-        match res {
-            0 => Ok(()),
-            x => Err(MinionError::from(RuntimeError::from(x))),
-        }
-    }
+    res
 }
 
-unsafe fn convert_model_to_raw(
+/// Adds the given model to a Minion instance, returning the variables in its print matrix.
+pub(crate) unsafe fn convert_model_to_raw(
     instance: *mut ffi::ProbSpec_CSPInstance,
     model: &Model,
-) -> Result<(), MinionError> {
+) -> Result<Vec<VarName>, MinionError> {
     /*******************************/
     /*        Add variables        */
     /*******************************/
@@ -214,16 +229,11 @@ unsafe fn convert_model_to_raw(
      * 1. symbol table
      * 2. print matrix
      * 3. search vars
-     *
-     * These are all done in the order saved in the SymbolTable.
      */
 
-    let search_vars = Scoped::new(ffi::vec_var_new(), |x| ffi::vec_var_free(x as _));
-
-    // store variables and the order they will be returned inside rust for later use.
-    #[allow(clippy::unwrap_used)]
-    let mut print_vars_guard = PRINT_VARS.lock().unwrap();
-    *print_vars_guard = Some(vec![]);
+    // libminion only takes the bounds of each variable, so SPARSEBOUND variables are added as
+    // DISCRETE variables, constrained to their values.
+    let mut sparse_bounds: Vec<Constraint> = vec![];
 
     for var_name in model.named_variables.get_variable_order() {
         let c_str = CString::new(var_name.clone()).map_err(|_| {
@@ -240,6 +250,14 @@ unsafe fn convert_model_to_raw(
 
         let (vartype_raw, domain_low, domain_high) = match vartype {
             VarDomain::Bound(a, b) => Ok((ffi::VariableType_VAR_BOUND, a, b)),
+            VarDomain::Discrete(a, b) => Ok((ffi::VariableType_VAR_DISCRETE, a, b)),
+            VarDomain::SparseBound(a, b) => {
+                sparse_bounds.push(Constraint::WInset(
+                    Var::NameRef(var_name.clone()),
+                    vec![Constant::Integer(a), Constant::Integer(b)],
+                ));
+                Ok((ffi::VariableType_VAR_DISCRETE, a.min(b), a.max(b)))
+            }
             VarDomain::Bool => Ok((ffi::VariableType_VAR_BOOL, 0, 1)), // TODO: will this work?
             x => Err(MinionError::NotImplemented(format!("{:?}", x))),
         }?;
@@ -251,32 +269,111 @@ unsafe fn convert_model_to_raw(
             domain_low,
             domain_high,
         );
+    }
 
-        let var = ffi::getVarByName(instance, c_str.as_ptr() as _);
+    // store variables and the order they will be returned inside rust for later use.
+    let print_vars = model
+        .print_vars
+        .clone()
+        .unwrap_or_else(|| model.named_variables.get_variable_order());
 
+    for var_name in &print_vars {
+        let var = get_named_var(instance, model, var_name)?;
         ffi::printMatrix_addVar(instance, var);
+    }
+
+    if model.search_orders.is_empty() {
+        // with no search orders given, search on all variables in declaration order.
+        let all_vars = SearchOrder::new(model.named_variables.get_variable_order());
+        add_search_order(instance, model, &all_vars, false)?;
+    } else {
+        for search_order in &model.search_orders {
+            add_search_order(instance, model, search_order, false)?;
+        }
+
+        // the remaining variables are auxiliary: Minion only needs to find one assignment to them.
+        let aux_vars: Vec<VarName> = model
+            .named_variables
+            .get_variable_order()
+            .into_iter()
+            .filter(|x| !model.search_orders.iter().any(|o| o.vars.contains(x)))
+            .collect();
+
+        add_search_order(instance, model, &SearchOrder::new(aux_vars), true)?;
+    }
 
-        // add to the print vars stored in rust so to remember
-        // the order for callback function.
+    add_constraints(instance, model.constraints.iter().chain(&sparse_bounds))?;
 
-        #[allow(clippy::unwrap_used)]
-        (*print_vars_guard).as_mut().unwrap().push(var_name.clone());
+    Ok(print_vars)
+}
 
+unsafe fn add_search_order(
+    instance: *mut ffi::ProbSpec_CSPInstance,
+    model: &Model,
+    search_order: &SearchOrder,
+    find_one_assignment: bool,
+) -> Result<(), MinionError> {
+    if search_order.vars.is_empty() {
+        return Ok(());
+    }
+
+    let var_order_raw = match search_order.var_order {
+        VarOrder::Static => ffi::VarOrderEnum_ORDER_STATIC,
+        VarOrder::Sdf => ffi::VarOrderEnum_ORDER_SDF,
+        VarOrder::Conflict => ffi::VarOrderEnum_ORDER_CONFLICT,
+    };
+
+    let value_order_raw = match search_order.value_order {
+        ValueOrder::Ascending => ffi::ValOrderEnum_VALORDER_ASCEND,
+        ValueOrder::Descending => ffi::ValOrderEnum_VALORDER_DESCEND,
+    };
+
+    let search_vars = Scoped::new(ffi::vec_var_new(), |x| ffi::vec_var_free(x as _));
+    for var_name in &search_order.vars {
+        let var = get_named_var(instance, model, var_name)?;
         ffi::vec_var_push_back(search_vars.ptr, var);
     }
 
-    let search_order = Scoped::new(
-        ffi::searchOrder_new(search_vars.ptr, ffi::VarOrderEnum_ORDER_STATIC, false),
+    let raw_search_order = Scoped::new(
+        ffi::searchOrder_new(search_vars.ptr, var_order_raw, find_one_assignment),
         |x| ffi::searchOrder_free(x as _),
     );
 
-    ffi::instance_addSearchOrder(instance, search_order.ptr);
+    // searchOrder_new sets the value order of each variable to ascending.
+    ffi::searchOrder_setValueOrder(raw_search_order.ptr, value_order_raw);
+
+    ffi::instance_addSearchOrder(instance, raw_search_order.ptr);
+    Ok(())
+}
+
+pub(crate) unsafe fn get_named_var(
+    instance: *mut ffi::ProbSpec_CSPInstance,
+    model: &Model,
+    var_name: &VarName,
+) -> Result<ffi::Var, MinionError> {
+    if !model.named_variables.contains(var_name.clone()) {
+        return Err(anyhow!("Variable {:?} is not in the symbol table.", var_name).into());
+    }
+
+    let c_str = CString::new(var_name.clone()).map_err(|_| {
+        anyhow!(
+            "Variable name {:?} contains a null character.",
+            var_name.clone()
+        )
+    })?;
+
+    Ok(ffi::getVarByName(instance, c_str.as_ptr() as _))
+}
 
+unsafe fn add_constraints<'a>(
+    instance: *mut ffi::ProbSpec_CSPInstance,
+    constraints: impl IntoIterator<Item = &'a Constraint>,
+) -> Result<(), MinionError> {
     /*********************************/
     /*        Add constraints        */
     /*********************************/
 
-    for constraint in &model.constraints {
+    for constraint in constraints {
         // 1. get constraint type and create C++ constraint object
         // 2. run through arguments and add them to the constraint
         // 3. add constraint to instance
@@ -534,18 +631,39 @@ unsafe fn constraint_add_args(
             Ok(())
         }
         //Constraint::LitSumGeq(_, _, _) => todo!(),
-        //Constraint::Gcc(_, _, _) => todo!(),
-        //Constraint::GccWeak(_, _, _) => todo!(),
+        Constraint::Gcc(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_constant_list(r_constr, b)?;
+            read_list(i, r_constr, c)?;
+            Ok(())
+        }
+        Constraint::GccWeak(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_constant_list(r_constr, b)?;
+            read_list(i, r_constr, c)?;
+            Ok(())
+        }
         //Constraint::LexLeqRv(_, _) => todo!(),
         //Constraint::LexLeq(_, _) => todo!(),
         //Constraint::LexLess(_, _) => todo!(),
         //Constraint::LexLeqQuick(_, _) => todo!(),
         //Constraint::LexLessQuick(_, _) => todo!(),
-        //Constraint::WatchVecNeq(_, _) => todo!(),
+        Constraint::WatchVecNeq(a, b) => {
+            read_list(i, r_constr, a)?;
+            read_list(i, r_constr, b)?;
+            Ok(())
+        }
         //Constraint::WatchVecExistsLess(_, _) => todo!(),
         //Constraint::Hamming(_, _, _) => todo!(),
         //Constraint::NotHamming(_, _, _) => todo!(),
-        //Constraint::FrameUpdate(_, _, _, _, _) => todo!(),
+        Constraint::FrameUpdate(a, b, c, d, e) => {
+            read_list(i, r_constr, a)?;
+            read_list(i, r_constr, b)?;
+            read_list(i, r_constr, c)?;
+            read_list(i, r_constr, d)?;
+            read_constant(r_constr, e)?;
+            Ok(())
+        }
         //Constraint::NegativeTable(_, _) => todo!(),
         //Constraint::Table(_, _) => todo!(),
         //Constraint::GacSchema(_, _) => todo!(),
@@ -553,41 +671,128 @@ unsafe fn constraint_add_args(
         //Constraint::Mddc(_, _) => todo!(),
         //Constraint::NegativeMddc(_, _) => todo!(),
         //Constraint::Str2Plus(_, _) => todo!(),
-        //Constraint::Max(_, _) => todo!(),
-        //Constraint::Min(_, _) => todo!(),
-        //Constraint::NvalueGeq(_, _) => todo!(),
-        //Constraint::NvalueLeq(_, _) => todo!(),
-        //Constraint::Element(_, _, _) => todo!(),
-        //Constraint::ElementOne(_, _, _) => todo!(),
-        //Constraint::ElementUndefZero(_, _, _) => todo!(),
-        //Constraint::WatchElement(_, _, _) => todo!(),
-        //Constraint::WatchElementOne(_, _, _) => todo!(),
-        //Constraint::WatchElementOneUndefZero(_, _, _) => todo!(),
-        //Constraint::WatchElementUndefZero(_, _, _) => todo!(),
+        Constraint::Max(a, b) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
+        Constraint::Min(a, b) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
+        Constraint::NvalueGeq(a, b) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
+        Constraint::NvalueLeq(a, b) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
+        Constraint::Element(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            read_var(i, r_constr, c)?;
+            Ok(())
+        }
+        Constraint::ElementOne(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            read_var(i, r_constr, c)?;
+            Ok(())
+        }
+        Constraint::ElementUndefZero(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            read_var(i, r_constr, c)?;
+            Ok(())
+        }
+        Constraint::WatchElement(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            read_var(i, r_constr, c)?;
+            Ok(())
+        }
+        Constraint::WatchElementOne(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            read_var(i, r_constr, c)?;
+            Ok(())
+        }
+        Constraint::WatchElementOneUndefZero(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            read_var(i, r_constr, c)?;
+            Ok(())
+        }
+        Constraint::WatchElementUndefZero(a, b, c) => {
+            read_list(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            read_var(i, r_constr, c)?;
+            Ok(())
+        }
         Constraint::WLiteral(a, b) => {
             read_var(i, r_constr, a)?;
             read_constant(r_constr, b)?;
             Ok(())
         }
-        //Constraint::WNotLiteral(_, _) => todo!(),
-        //Constraint::WInIntervalSet(_, _) => todo!(),
-        //Constraint::WInRange(_, _) => todo!(),
+        Constraint::WNotLiteral(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_constant(r_constr, b)?;
+            Ok(())
+        }
+        Constraint::WInIntervalSet(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_constant_list(r_constr, b)?;
+            Ok(())
+        }
+        Constraint::WInRange(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_constant_list(r_constr, b)?;
+            Ok(())
+        }
         Constraint::WInset(a, b) => {
             read_var(i, r_constr, a)?;
             read_constant_list(r_constr, b)?;
             Ok(())
         }
-        //Constraint::WNotInRange(_, _) => todo!(),
-        //Constraint::WNotInset(_, _) => todo!(),
-        //Constraint::Abs(_, _) => todo!(),
+        Constraint::WNotInRange(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_constant_list(r_constr, b)?;
+            Ok(())
+        }
+        Constraint::WNotInset(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_constant_list(r_constr, b)?;
+            Ok(())
+        }
+        Constraint::Abs(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
         Constraint::DisEq(a, b) => {
             read_var(i, r_constr, a)?;
             read_var(i, r_constr, b)?;
             Ok(())
         }
-        //Constraint::MinusEq(_, _) => todo!(),
-        //Constraint::GacEq(_, _) => todo!(),
-        //Constraint::WatchLess(_, _) => todo!(),
+        Constraint::MinusEq(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
+        Constraint::GacEq(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
+        Constraint::WatchLess(a, b) => {
+            read_var(i, r_constr, a)?;
+            read_var(i, r_constr, b)?;
+            Ok(())
+        }
         // TODO: ensure that this is a bool?
         Constraint::WatchNeq(a, b) => {
             read_var(i, r_constr, a)?;
@@ -0,0 +1,469 @@
+//! Running Minion in child worker processes.
+//!
+//! Minion keeps its state in globals, so [run_minion](crate::run_minion) only runs one model at a
+//! time per process. A [WorkerPool] instead runs each model in its own `minion_worker` process, so
+//! that several models can be solved at once.
+//!
+//! Models are sent to the worker over its standard input in Minion's textual input format (see
+//! [print](crate::print)). Solutions and run statistics are sent back over its standard output,
+//! one per line.
+
+use std::collections::HashMap;
+use std::io::{BufRead, BufReader, BufWriter, Read, Write};
+use std::path::{Path, PathBuf};
+use std::process::{Command, ExitCode, Stdio};
+use std::str::FromStr;
+use std::sync::{Condvar, Mutex};
+
+use anyhow::anyhow;
+
+use crate::ast::{Constant, Model, VarName};
+use crate::error::{MinionError, RuntimeError};
+use crate::parse::parse_minion_file;
+use crate::print::write_minion_file;
+use crate::{get_from_table, run_minion_with_options, PropagationLevel, SearchOptions};
+
+/// The name of the worker binary.
+const WORKER_NAME: &str = "minion_worker";
+
+/// The environment variable used to override the location of the worker binary.
+const WORKER_ENV_VAR: &str = "MINION_RS_WORKER";
+
+/// The entries of Minion's run statistics that workers send back.
+const TABLE_KEYS: [&str; 6] = [
+    "Nodes",
+    "SolveTime",
+    "SetupTime",
+    "SolutionsFound",
+    "Satisfiable",
+    "TimeOut",
+];
+
+/// A pool of `minion_worker` processes.
+///
+/// At most `max_workers` models are solved at once; further calls to [WorkerPool::run] wait for
+/// a worker to become free. Each model is solved in a fresh process, so a crash in Minion does not
+/// affect other runs.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::sync::Arc;
+/// use std::thread;
+///
+/// use minion_rs::ast::Model;
+/// use minion_rs::worker::WorkerPool;
+/// use minion_rs::SearchOptions;
+///
+/// let pool = Arc::new(WorkerPool::with_default_worker(4).unwrap());
+///
+/// let handles: Vec<_> = (0..8)
+///     .map(|_| {
+///         let pool = pool.clone();
+///         thread::spawn(move || {
+///             let model = Model::new();
+///             // ... omitted for brevity ...
+///             pool.run(&model, &SearchOptions::default(), |_| true)
+///         })
+///     })
+///     .collect();
+///
+/// for handle in handles {
+///     handle.join().unwrap().unwrap();
+/// }
+/// ```
+#[derive(Debug)]
+pub struct WorkerPool {
+    worker: PathBuf,
+    max_workers: usize,
+    running: Mutex<usize>,
+    available: Condvar,
+}
+
+impl WorkerPool {
+    /// Creates a pool that runs at most `max_workers` instances of the given worker binary at once.
+    pub fn new(worker: impl Into<PathBuf>, max_workers: usize) -> WorkerPool {
+        WorkerPool {
+            worker: worker.into(),
+            max_workers: max_workers.max(1),
+            running: Mutex::new(0),
+            available: Condvar::new(),
+        }
+    }
+
+    /// Creates a pool using the worker binary found by [find_worker].
+    pub fn with_default_worker(max_workers: usize) -> Result<WorkerPool, MinionError> {
+        Ok(WorkerPool::new(find_worker()?, max_workers))
+    }
+
+    /// The path of the worker binary used by this pool.
+    pub fn worker(&self) -> &Path {
+        &self.worker
+    }
+
+    /// Runs Minion on the given [Model] in a worker process, using the given [SearchOptions].
+    ///
+    /// The given callback is ran whenever a new solution set is found, as in
+    /// [run_minion](crate::run_minion).
+    ///
+    /// Returns the worker's run statistics, as given by [get_from_table]. Anything Minion printed
+    /// to its standard error is logged as a warning.
+    pub fn run<F>(
+        &self,
+        model: &Model,
+        options: &SearchOptions,
+        mut callback: F,
+    ) -> Result<HashMap<String, String>, MinionError>
+    where
+        F: FnMut(HashMap<VarName, Constant>) -> bool,
+    {
+        let _slot = self.acquire();
+
+        let mut child = Command::new(&self.worker)
+            .args(options.to_args())
+            .stdin(Stdio::piped())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .spawn()
+            .map_err(|e| anyhow!("could not start {}: {}", self.worker.display(), e))?;
+
+        // collect the worker's diagnostics, to report them if it crashes.
+        #[allow(clippy::expect_used)]
+        let mut stderr = child.stderr.take().expect("stderr is piped");
+        let stderr_thread = std::thread::spawn(move || {
+            let mut diagnostics = String::new();
+            let _ = stderr.read_to_string(&mut diagnostics);
+            diagnostics
+        });
+
+        #[allow(clippy::expect_used)]
+        let mut input = BufWriter::new(child.stdin.take().expect("stdin is piped"));
+        #[allow(clippy::expect_used)]
+        let output = BufReader::new(child.stdout.take().expect("stdout is piped"));
+
+        write_minion_file(&mut input, model).map_err(worker_io_error)?;
+        input.flush().map_err(worker_io_error)?;
+
+        let mut table: HashMap<String, String> = HashMap::new();
+        let mut result: Option<Result<(), MinionError>> = None;
+
+        for line in output.lines() {
+            let line = line.map_err(worker_io_error)?;
+            let (message, rest) = line.split_once(' ').unwrap_or((&line, ""));
+            match message {
+                "SOLUTION" => {
+                    let solutions = parse_solution(rest)?;
+                    let reply = if callback(solutions) {
+                        "CONTINUE"
+                    } else {
+                        "STOP"
+                    };
+                    writeln!(input, "{}", reply).map_err(worker_io_error)?;
+                    input.flush().map_err(worker_io_error)?;
+                }
+                "STAT" => {
+                    let (key, value) = rest.split_once(' ').unwrap_or((rest, ""));
+                    table.insert(key.to_owned(), value.to_owned());
+                }
+                "DONE" => {
+                    result = Some(Ok(()));
+                    break;
+                }
+                "ERROR" => {
+                    result = Some(Err(decode_error(rest)));
+                    break;
+                }
+                _ => {
+                    return Err(anyhow!("unexpected message from Minion worker: {:?}", line).into());
+                }
+            }
+        }
+
+        drop(input);
+        let status = child.wait().map_err(worker_io_error)?;
+        let diagnostics = stderr_thread.join().unwrap_or_default();
+
+        match result {
+            Some(result) => {
+                if !diagnostics.trim().is_empty() {
+                    log::warn!("Minion worker: {}", diagnostics.trim());
+                }
+                result.map(|()| table)
+            }
+            None => Err(anyhow!(
+                "Minion worker exited unexpectedly ({}): {}",
+                status,
+                diagnostics.trim()
+            )
+            .into()),
+        }
+    }
+
+    /// Waits for a free worker slot.
+    #[allow(clippy::unwrap_used)]
+    fn acquire(&self) -> WorkerSlot<'_> {
+        let mut running = self
+            .available
+            .wait_while(self.running.lock().unwrap(), |running| {
+                *running >= self.max_workers
+            })
+            .unwrap();
+        *running += 1;
+        WorkerSlot { pool: self }
+    }
+}
+
+/// A running worker; frees its slot in the pool on drop.
+struct WorkerSlot<'a> {
+    pool: &'a WorkerPool,
+}
+
+impl Drop for WorkerSlot<'_> {
+    #[allow(clippy::unwrap_used)]
+    fn drop(&mut self) {
+        *self.pool.running.lock().unwrap() -= 1;
+        self.pool.available.notify_one();
+    }
+}
+
+/// Finds the `minion_worker` binary.
+///
+/// This is the path in the `MINION_RS_WORKER` environment variable if it is set. Otherwise, it is
+/// looked for next to the current executable, then in its parent directory (where Cargo puts
+/// binaries relative to test executables).
+///
+/// Cargo does not build the binaries of dependencies, so crates using minion_rs should either
+/// build the worker themselves (`cargo build -p minion_rs --bin minion_worker`) and set
+/// `MINION_RS_WORKER`, or give its path to [WorkerPool::new].
+pub fn find_worker() -> Result<PathBuf, MinionError> {
+    if let Some(path) = std::env::var_os(WORKER_ENV_VAR) {
+        let path = PathBuf::from(path);
+        if !path.is_file() {
+            return Err(anyhow!(
+                "{} is set to {}, but there is no {} binary there",
+                WORKER_ENV_VAR,
+                path.display(),
+                WORKER_NAME
+            )
+            .into());
+        }
+        return Ok(path);
+    }
+
+    let name = format!("{}{}", WORKER_NAME, std::env::consts::EXE_SUFFIX);
+    let exe = std::env::current_exe()
+        .map_err(|e| anyhow!("could not find the current executable: {}", e))?;
+    exe.ancestors()
+        .skip(1)
+        .take(2)
+        .map(|dir| dir.join(&name))
+        .find(|path| path.is_file())
+        .ok_or_else(|| {
+            anyhow!(
+                "could not find the {} binary next to {}; build it with `cargo build -p minion_rs --bin {}`, and set {} to its path",
+                WORKER_NAME,
+                exe.display(),
+                WORKER_NAME,
+                WORKER_ENV_VAR
+            )
+            .into()
+        })
+}
+
+/// The entry point of the `minion_worker` binary.
+///
+/// Reads a model from standard input, solves it with the search options given as command line
+/// arguments, and writes its solutions and run statistics to standard output.
+pub fn worker_main() -> ExitCode {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    let mut input = std::io::stdin().lock();
+    let mut output = std::io::stdout().lock();
+
+    match run_worker(&args, &mut input, &mut output) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("{}: {}", WORKER_NAME, e);
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run_worker(
+    args: &[String],
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> std::io::Result<()> {
+    let options = match parse_args(args) {
+        Ok(options) => options,
+        Err(e) => {
+            writeln!(output, "ERROR {}", encode_error(&e))?;
+            return output.flush();
+        }
+    };
+
+    // read the model, up to and including its **EOF** marker.
+    let mut text = String::new();
+    loop {
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            break;
+        }
+        text.push_str(&line);
+        if line.trim() == "**EOF**" {
+            break;
+        }
+    }
+
+    let model = match parse_minion_file(&text) {
+        Ok(model) => model,
+        Err(e) => {
+            writeln!(output, "ERROR {}", encode_error(&e.into()))?;
+            return output.flush();
+        }
+    };
+
+    let mut io_error: Option<std::io::Error> = None;
+    let res = run_minion_with_options(model, &options, |solutions| {
+        match send_solution(input, output, solutions) {
+            Ok(continue_search) => continue_search,
+            Err(e) => {
+                io_error = Some(e);
+                false
+            }
+        }
+    });
+
+    if let Some(e) = io_error {
+        return Err(e);
+    }
+
+    match res {
+        Ok(()) => {
+            for key in TABLE_KEYS {
+                if let Some(value) = get_from_table(key.into()) {
+                    writeln!(output, "STAT {} {}", key, value.trim())?;
+                }
+            }
+            writeln!(output, "DONE")?;
+        }
+        Err(e) => writeln!(output, "ERROR {}", encode_error(&e))?,
+    }
+
+    output.flush()
+}
+
+/// Sends a solution to the parent process, returning whether search should continue.
+fn send_solution(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    solutions: HashMap<VarName, Constant>,
+) -> std::io::Result<bool> {
+    write!(output, "SOLUTION")?;
+    for (name, value) in solutions {
+        match value {
+            Constant::Integer(x) => write!(output, " {} {}", name, x)?,
+            Constant::Bool(x) => write!(output, " {} {}", name, i32::from(x))?,
+        }
+    }
+    writeln!(output)?;
+    output.flush()?;
+
+    let mut reply = String::new();
+    input.read_line(&mut reply)?;
+    Ok(reply.trim() == "CONTINUE")
+}
+
+fn parse_solution(line: &str) -> Result<HashMap<VarName, Constant>, MinionError> {
+    let words: Vec<&str> = line.split_whitespace().collect();
+    words
+        .chunks(2)
+        .map(|pair| match pair {
+            [name, value] => value
+                .parse::<i32>()
+                .map(|x| (name.to_string(), Constant::Integer(x)))
+                .map_err(|_| anyhow!("invalid value in solution from Minion worker: {}", line)),
+            _ => Err(anyhow!("invalid solution from Minion worker: {}", line)),
+        })
+        .collect::<Result<_, _>>()
+        .map_err(MinionError::from)
+}
+
+/// Parses the search options from the output of [SearchOptions::to_args].
+fn parse_args(args: &[String]) -> Result<SearchOptions, MinionError> {
+    fn value<T: FromStr>(flag: &str, value: Option<&String>) -> Result<T, MinionError> {
+        value
+            .and_then(|x| x.parse().ok())
+            .ok_or_else(|| anyhow!("invalid or missing value for {}", flag).into())
+    }
+
+    let mut options = SearchOptions::default();
+    let mut args = args.iter();
+    while let Some(flag) = args.next() {
+        match flag.as_str() {
+            "-nodelimit" => options.node_limit = Some(value(flag, args.next())?),
+            "-timelimit" => options.time_limit_s = Some(value(flag, args.next())?),
+            "-sollimit" => options.solution_limit = Some(value(flag, args.next())?),
+            "-findallsols" => options.solution_limit = None,
+            "-randomseed" => options.random_seed = Some(value(flag, args.next())?),
+            "-restarts" => options.restarts = true,
+            "-preprocess" => {
+                options.preprocess = Some(value::<PropagationLevel>(flag, args.next())?)
+            }
+            x => return Err(anyhow!("unknown argument {}", x).into()),
+        }
+    }
+    Ok(options)
+}
+
+fn encode_error(err: &MinionError) -> String {
+    let (kind, message) = match err {
+        MinionError::RuntimeError(x) => {
+            let kind = match x {
+                RuntimeError::InvalidInstance { .. } => "InvalidInstance",
+                _ => "UnknownError",
+            };
+            (kind, x.details().unwrap_or_default().to_owned())
+        }
+        MinionError::NotImplemented(x) => ("NotImplemented", x.clone()),
+        x => ("Other", x.to_string()),
+    };
+
+    // messages must fit on one line.
+    let message = message.replace('\\', "\\\\").replace('\n', "\\n");
+    format!("{} {}", kind, message).trim_end().to_owned()
+}
+
+fn decode_error(line: &str) -> MinionError {
+    let (kind, message) = line.split_once(' ').unwrap_or((line, ""));
+    let message = unescape(message);
+    let details = Some(message.clone()).filter(|x| !x.is_empty());
+    match kind {
+        "InvalidInstance" => RuntimeError::InvalidInstance { details }.into(),
+        "UnknownError" => RuntimeError::UnknownError { details }.into(),
+        "NotImplemented" => MinionError::NotImplemented(message),
+        _ => anyhow!("{}", message).into(),
+    }
+}
+
+fn unescape(message: &str) -> String {
+    let mut unescaped = String::new();
+    let mut chars = message.chars();
+    while let Some(c) = chars.next() {
+        match (c, chars.clone().next()) {
+            ('\\', Some('n')) => {
+                unescaped.push('\n');
+                chars.next();
+            }
+            ('\\', Some('\\')) => {
+                unescaped.push('\\');
+                chars.next();
+            }
+            (c, _) => unescaped.push(c),
+        }
+    }
+    unescaped
+}
+
+fn worker_io_error(err: std::io::Error) -> MinionError {
+    anyhow!("could not communicate with Minion worker: {}", err).into()
+}
@@ -28,3 +28,18 @@ pub fn get_from_table(key: String) -> Option<String> {
         }
     }
 }
+
+/// Gets the diagnostics of the last failed run of Minion: anything it printed to standard error,
+/// and the message of the exception it failed with.
+///
+/// These are kept by libminion_ext, so this must be called before Minion is next ran.
+pub(crate) unsafe fn get_last_error() -> Option<String> {
+    let ptr = ffi::getLastError();
+    if ptr.is_null() {
+        return None;
+    }
+
+    // the string is owned by libminion_ext, so is copied rather than freed.
+    let error = CStr::from_ptr(ptr).to_string_lossy().trim().to_owned();
+    Some(error).filter(|x| !x.is_empty())
+}
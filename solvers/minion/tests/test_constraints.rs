@@ -0,0 +1,256 @@
+//! Small solve tests for individual constraints.
+//!
+//! Each case is a Minion model with a single constraint over `BOUND` variables, such as:
+//!
+//! ```text
+//! #TEST SOLCOUNT 5
+//! MINION 3
+//!
+//! **VARIABLES**
+//! BOUND x {0..2}
+//! BOUND y {-2..2}
+//!
+//! **CONSTRAINTS**
+//!
+//! abs(x,y)
+//!
+//! **EOF**
+//! ```
+//!
+//! and checks the number of solutions that Minion finds.
+
+use minion_rs::ast::{Constant, Constraint, Model, Var, VarDomain};
+use minion_rs::error::MinionError;
+
+/// A single constraint, and the number of solutions it has over the given variables.
+struct Case {
+    /// The constraint, as written in a Minion file.
+    minion: &'static str,
+
+    /// The name, lower bound, and upper bound of each variable.
+    vars: &'static [(&'static str, i32, i32)],
+
+    constraint: Constraint,
+    solutions: usize,
+}
+
+fn var(name: &str) -> Var {
+    Var::NameRef(String::from(name))
+}
+
+fn vars(names: &[&str]) -> Vec<Var> {
+    names.iter().map(|x| var(x)).collect()
+}
+
+fn ints(values: &[i32]) -> Vec<Constant> {
+    values.iter().map(|x| Constant::Integer(*x)).collect()
+}
+
+fn cases() -> Vec<Case> {
+    vec![
+        Case {
+            minion: "abs(x,y)",
+            vars: &[("x", 0, 2), ("y", -2, 2)],
+            constraint: Constraint::Abs(var("x"), var("y")),
+            solutions: 5,
+        },
+        Case {
+            minion: "element([a,b],i,e)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("i", 0, 1), ("e", 2, 3)],
+            constraint: Constraint::Element(vars(&["a", "b"]), var("i"), var("e")),
+            solutions: 4,
+        },
+        Case {
+            minion: "element_undefzero([a,b],i,e)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("i", 0, 2), ("e", 0, 2)],
+            constraint: Constraint::ElementUndefZero(vars(&["a", "b"]), var("i"), var("e")),
+            solutions: 12,
+        },
+        Case {
+            minion: "elementone([a,b],i,e)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("i", 1, 2), ("e", 2, 3)],
+            constraint: Constraint::ElementOne(vars(&["a", "b"]), var("i"), var("e")),
+            solutions: 4,
+        },
+        Case {
+            minion: "frameupdate([a0,a1],[b0,b1],[ia],[ib],1)",
+            vars: &[
+                ("a0", 0, 1),
+                ("a1", 0, 1),
+                ("b0", 0, 1),
+                ("b1", 0, 1),
+                ("ia", 0, 1),
+                ("ib", 0, 1),
+            ],
+            constraint: Constraint::FrameUpdate(
+                vars(&["a0", "a1"]),
+                vars(&["b0", "b1"]),
+                vars(&["ia"]),
+                vars(&["ib"]),
+                Constant::Integer(1),
+            ),
+            solutions: 32,
+        },
+        Case {
+            minion: "gaceq(x,y)",
+            vars: &[("x", 1, 3), ("y", 2, 4)],
+            constraint: Constraint::GacEq(var("x"), var("y")),
+            solutions: 2,
+        },
+        Case {
+            minion: "gcc([a,b,c],[1,2],[c1,c2])",
+            vars: &[
+                ("a", 1, 2),
+                ("b", 1, 2),
+                ("c", 1, 2),
+                ("c1", 0, 3),
+                ("c2", 1, 1),
+            ],
+            constraint: Constraint::Gcc(vars(&["a", "b", "c"]), ints(&[1, 2]), vars(&["c1", "c2"])),
+            solutions: 3,
+        },
+        Case {
+            minion: "gccweak([a,b,c],[1,2],[c1,c2])",
+            vars: &[
+                ("a", 1, 2),
+                ("b", 1, 2),
+                ("c", 1, 2),
+                ("c1", 0, 3),
+                ("c2", 1, 1),
+            ],
+            constraint: Constraint::GccWeak(
+                vars(&["a", "b", "c"]),
+                ints(&[1, 2]),
+                vars(&["c1", "c2"]),
+            ),
+            solutions: 3,
+        },
+        Case {
+            minion: "max([a,b],m)",
+            vars: &[("a", 1, 3), ("b", 1, 3), ("m", 2, 3)],
+            constraint: Constraint::Max(vars(&["a", "b"]), var("m")),
+            solutions: 8,
+        },
+        Case {
+            minion: "min([a,b],m)",
+            vars: &[("a", 1, 3), ("b", 1, 3), ("m", 1, 2)],
+            constraint: Constraint::Min(vars(&["a", "b"]), var("m")),
+            solutions: 8,
+        },
+        Case {
+            minion: "minuseq(x,y)",
+            vars: &[("x", -1, 3), ("y", -2, 2)],
+            constraint: Constraint::MinusEq(var("x"), var("y")),
+            solutions: 4,
+        },
+        Case {
+            minion: "nvaluegeq([a,b,c],x)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("c", 1, 2), ("x", 2, 2)],
+            constraint: Constraint::NvalueGeq(vars(&["a", "b", "c"]), var("x")),
+            solutions: 6,
+        },
+        Case {
+            minion: "nvalueleq([a,b,c],x)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("c", 1, 2), ("x", 1, 1)],
+            constraint: Constraint::NvalueLeq(vars(&["a", "b", "c"]), var("x")),
+            solutions: 2,
+        },
+        Case {
+            minion: "w-inintervalset(x,[1,2,4,4])",
+            vars: &[("x", 1, 5)],
+            constraint: Constraint::WInIntervalSet(var("x"), ints(&[1, 2, 4, 4])),
+            solutions: 3,
+        },
+        Case {
+            minion: "w-inrange(x,[2,3])",
+            vars: &[("x", 1, 4)],
+            constraint: Constraint::WInRange(var("x"), ints(&[2, 3])),
+            solutions: 2,
+        },
+        Case {
+            minion: "w-notinrange(x,[2,3])",
+            vars: &[("x", 1, 4)],
+            constraint: Constraint::WNotInRange(var("x"), ints(&[2, 3])),
+            solutions: 2,
+        },
+        Case {
+            minion: "w-notinset(x,[1,3])",
+            vars: &[("x", 1, 4)],
+            constraint: Constraint::WNotInset(var("x"), ints(&[1, 3])),
+            solutions: 2,
+        },
+        Case {
+            minion: "w-notliteral(x,2)",
+            vars: &[("x", 1, 3)],
+            constraint: Constraint::WNotLiteral(var("x"), Constant::Integer(2)),
+            solutions: 2,
+        },
+        Case {
+            minion: "watchelement([a,b],i,e)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("i", 0, 1), ("e", 2, 3)],
+            constraint: Constraint::WatchElement(vars(&["a", "b"]), var("i"), var("e")),
+            solutions: 4,
+        },
+        Case {
+            minion: "watchelement_one([a,b],i,e)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("i", 1, 2), ("e", 2, 3)],
+            constraint: Constraint::WatchElementOne(vars(&["a", "b"]), var("i"), var("e")),
+            solutions: 4,
+        },
+        Case {
+            minion: "watchelement_one_undefzero([a,b],i,e)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("i", 1, 3), ("e", 0, 2)],
+            constraint: Constraint::WatchElementOneUndefZero(vars(&["a", "b"]), var("i"), var("e")),
+            solutions: 12,
+        },
+        Case {
+            minion: "watchelement_undefzero([a,b],i,e)",
+            vars: &[("a", 1, 2), ("b", 1, 2), ("i", 0, 2), ("e", 0, 2)],
+            constraint: Constraint::WatchElementUndefZero(vars(&["a", "b"]), var("i"), var("e")),
+            solutions: 12,
+        },
+        Case {
+            minion: "watchless(x,y)",
+            vars: &[("x", 1, 3), ("y", 1, 3)],
+            constraint: Constraint::WatchLess(var("x"), var("y")),
+            solutions: 3,
+        },
+        Case {
+            minion: "watchvecneq([a,b],[c,d])",
+            vars: &[("a", 0, 1), ("b", 0, 1), ("c", 0, 1), ("d", 0, 1)],
+            constraint: Constraint::WatchVecNeq(vars(&["a", "b"]), vars(&["c", "d"])),
+            solutions: 12,
+        },
+    ]
+}
+
+fn count_solutions(case: &Case) -> Result<usize, MinionError> {
+    let mut model = Model::new();
+    for &(name, lb, ub) in case.vars {
+        model
+            .named_variables
+            .add_var(String::from(name), VarDomain::Bound(lb, ub));
+    }
+    model.constraints.push(case.constraint.clone());
+
+    let mut solutions = 0;
+    minion_rs::run_minion(model, |_| {
+        solutions += 1;
+        true
+    })?;
+    Ok(solutions)
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_constraints() -> Result<(), MinionError> {
+    for case in cases() {
+        assert_eq!(
+            count_solutions(&case)?,
+            case.solutions,
+            "wrong number of solutions for {}",
+            case.minion
+        );
+    }
+    Ok(())
+}
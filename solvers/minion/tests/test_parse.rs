@@ -0,0 +1,239 @@
+//! Tests for [minion_rs::parse], using this minion test file:
+//! https://github.com/minion/minion/blob/main/test_instances/test_watchedor_reifyimply_1.minion
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use minion_rs::ast::{
+    Constant, Constraint, Model, SearchOrder, ValueOrder, Var, VarDomain, VarName, VarOrder,
+};
+use minion_rs::error::{MinionError, ParseErrorKind};
+use minion_rs::parse::parse_minion_file;
+use minion_rs::print::write_minion_file;
+
+const WATCHEDOR_REIFYIMPLY_1: &str = "#TEST SOLCOUNT 7
+# Recursive test
+MINION 3
+
+**VARIABLES**
+BOOL a
+BOOL b
+BOOL c
+
+**CONSTRAINTS**
+
+reifyimply(watched-or({w-inset(a,[1]),w-inset(b,[0])}), c)
+
+**EOF**
+";
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_parse_and_solve() -> Result<(), MinionError> {
+    let model = parse_minion_file(WATCHEDOR_REIFYIMPLY_1)?;
+
+    assert_eq!(
+        model.constraints,
+        vec![Constraint::ReifyImply(
+            Box::new(Constraint::WatchedOr(vec![
+                Constraint::WInset(Var::NameRef(String::from("a")), vec![Constant::Integer(1)]),
+                Constraint::WInset(Var::NameRef(String::from("b")), vec![Constant::Integer(0)]),
+            ])),
+            Var::NameRef(String::from("c")),
+        )]
+    );
+
+    minion_rs::run_minion(model, callback)?;
+
+    let guard = SOLS_COUNTER.lock().unwrap();
+    assert_eq!(*guard, 7);
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_parse_and_solve_discrete_domains() -> Result<(), MinionError> {
+    let model = parse_minion_file(
+        "MINION 3
+**VARIABLES**
+DISCRETE x {1..3}
+SPARSEBOUND y {2,5}
+**CONSTRAINTS**
+ineq(x, y, -1)
+**EOF**",
+    )?;
+
+    // x < y: x = 1 when y = 2, and x in 1..3 when y = 5.
+    let mut solutions = vec![];
+    minion_rs::run_minion(model, |solution| {
+        solutions.push((solution["x"], solution["y"]));
+        true
+    })?;
+
+    let expected = [(1, 2), (1, 5), (2, 5), (3, 5)];
+    assert_eq!(solutions.len(), expected.len());
+    for (x, y) in expected {
+        assert!(solutions.contains(&(Constant::Integer(x), Constant::Integer(y))));
+    }
+    Ok(())
+}
+
+#[test]
+fn test_parse_roundtrip() {
+    let mut model = Model::new();
+    model
+        .named_variables
+        .add_var(String::from("x"), VarDomain::Discrete(-2, 3));
+    model
+        .named_variables
+        .add_var(String::from("y"), VarDomain::SparseBound(1, 5));
+    model
+        .named_variables
+        .add_var(String::from("z"), VarDomain::Bool);
+
+    let x = Var::NameRef(String::from("x"));
+    let y = Var::NameRef(String::from("y"));
+    let z = Var::NameRef(String::from("z"));
+
+    model.constraints.push(Constraint::Table(
+        vec![x.clone(), y.clone()],
+        vec![
+            (Constant::Integer(-2), Constant::Integer(1)),
+            (Constant::Integer(3), Constant::Integer(5)),
+        ],
+    ));
+    model.constraints.push(Constraint::Reify(
+        Box::new(Constraint::CheckAssign(Box::new(Constraint::Ineq(
+            x.clone(),
+            Var::ConstantAsVar(-1),
+            Constant::Integer(0),
+        )))),
+        z.clone(),
+    ));
+    model.constraints.push(Constraint::LexLeqRv(
+        vec![x.clone(), y.clone()],
+        vec![y, Var::ConstantAsVar(4)],
+    ));
+    model.constraints.push(Constraint::WatchedAnd(vec![
+        Constraint::True,
+        Constraint::False,
+    ]));
+    model.constraints.push(Constraint::WeightedSumLeq(
+        vec![Constant::Integer(2), Constant::Integer(-3)],
+        vec![x, z],
+        Var::ConstantAsVar(7),
+    ));
+
+    model.search_orders.push(SearchOrder {
+        vars: vec![String::from("y")],
+        var_order: VarOrder::Sdf,
+        value_order: ValueOrder::Descending,
+    });
+    model
+        .search_orders
+        .push(SearchOrder::new(vec![String::from("x")]));
+    model.print_vars = Some(vec![String::from("x"), String::from("z")]);
+
+    let mut buf: Vec<u8> = Vec::new();
+    write_minion_file(&mut buf, &model).unwrap();
+    let parsed = parse_minion_file(&String::from_utf8(buf).unwrap()).unwrap();
+
+    assert_eq!(parsed, model);
+}
+
+#[test]
+fn test_parse_tuplelist() {
+    let model = parse_minion_file(
+        "MINION 3
+**VARIABLES**
+DISCRETE x # comment between a name and its domain
+{1..3}
+BOUND y {1..3}
+**TUPLELIST**
+pairs 2 2
+1 2
+2 3
+**SEARCH**
+PRINT [[x],[y]]
+VARORDER STATIC [x,y]
+**CONSTRAINTS**
+table([x,y], pairs)
+**EOF**",
+    )
+    .unwrap();
+
+    assert_eq!(
+        model.constraints,
+        vec![Constraint::Table(
+            vec![
+                Var::NameRef(String::from("x")),
+                Var::NameRef(String::from("y"))
+            ],
+            vec![
+                (Constant::Integer(1), Constant::Integer(2)),
+                (Constant::Integer(2), Constant::Integer(3)),
+            ],
+        )]
+    );
+    assert_eq!(
+        model.search_orders,
+        vec![SearchOrder::new(vec![String::from("x"), String::from("y")])]
+    );
+    assert_eq!(
+        model.print_vars,
+        Some(vec![String::from("x"), String::from("y")])
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    let err = parse_minion_file(
+        "MINION 3
+**VARIABLES**
+BOOL a
+**CONSTRAINTS**
+eq(a, 1)
+  shortstr2([a], {})
+**EOF**",
+    )
+    .unwrap_err();
+    assert_eq!((err.line, err.column), (6, 3));
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::UnsupportedConstraint(String::from("shortstr2"))
+    );
+
+    let err = parse_minion_file(
+        "MINION 3
+**VARIABLES**
+BOOL a
+**CONSTRAINTS**
+diseq(a, b)
+**EOF**",
+    )
+    .unwrap_err();
+    assert_eq!((err.line, err.column), (5, 10));
+    assert_eq!(err.kind, ParseErrorKind::UnknownVariable(String::from("b")));
+
+    let err = parse_minion_file(
+        "MINION 3
+**VARIABLES**
+BOOL a
+BOUND a {1..2}
+**EOF**",
+    )
+    .unwrap_err();
+    assert_eq!((err.line, err.column), (4, 7));
+    assert_eq!(
+        err.kind,
+        ParseErrorKind::DuplicateVariable(String::from("a"))
+    );
+}
+
+static SOLS_COUNTER: Mutex<i32> = Mutex::new(0);
+fn callback(_: HashMap<VarName, Constant>) -> bool {
+    #[allow(clippy::unwrap_used)]
+    let mut guard = SOLS_COUNTER.lock().unwrap();
+    *guard += 1;
+    true
+}
@@ -0,0 +1,82 @@
+//! Runs root propagation on models where it narrows domains, and on the following model, which
+//! fails at the root node:
+//!
+//! ```text
+//! MINION 3
+//!
+//! **VARIABLES**
+//! BOUND x {1..2}
+//! BOUND y {1..2}
+//!
+//! **CONSTRAINTS**
+//! ineq(x,y,-1)
+//! ineq(y,x,-1)
+//!
+//! **EOF**
+//! ```
+
+use std::collections::BTreeMap;
+
+use minion_rs::ast::{Constant, Constraint, Model, Var, VarDomain};
+use minion_rs::error::MinionError;
+use minion_rs::propagate::{propagate_root, RootPropagation};
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_propagate_root_failure() -> Result<(), MinionError> {
+    let mut model = Model::new();
+    model
+        .named_variables
+        .add_var(String::from("x"), VarDomain::Bound(1, 2));
+    model
+        .named_variables
+        .add_var(String::from("y"), VarDomain::Bound(1, 2));
+
+    model.constraints.push(Constraint::Ineq(
+        Var::NameRef(String::from("x")),
+        Var::NameRef(String::from("y")),
+        Constant::Integer(-1),
+    ));
+    model.constraints.push(Constraint::Ineq(
+        Var::NameRef(String::from("y")),
+        Var::NameRef(String::from("x")),
+        Constant::Integer(-1),
+    ));
+
+    assert_eq!(propagate_root(&model)?, RootPropagation::Failed);
+    Ok(())
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_propagate_root_narrows_domains() -> Result<(), MinionError> {
+    // x < y, with x and y in 1..3, and z in 1..5 but not 3.
+    let mut model = Model::new();
+    model
+        .named_variables
+        .add_var(String::from("x"), VarDomain::Bound(1, 3));
+    model
+        .named_variables
+        .add_var(String::from("y"), VarDomain::Bound(1, 3));
+    model
+        .named_variables
+        .add_var(String::from("z"), VarDomain::Discrete(1, 5));
+
+    model.constraints.push(Constraint::Ineq(
+        Var::NameRef(String::from("x")),
+        Var::NameRef(String::from("y")),
+        Constant::Integer(-1),
+    ));
+    model.constraints.push(Constraint::WNotLiteral(
+        Var::NameRef(String::from("z")),
+        Constant::Integer(3),
+    ));
+
+    let expected = BTreeMap::from([
+        (String::from("x"), vec![1, 2]),
+        (String::from("y"), vec![2, 3]),
+        (String::from("z"), vec![1, 2, 4, 5]),
+    ]);
+    assert_eq!(propagate_root(&model)?, RootPropagation::Domains(expected));
+    Ok(())
+}
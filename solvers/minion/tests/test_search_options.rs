@@ -0,0 +1,55 @@
+//! Runs the following model with a solution limit of 2:
+//!
+//! ```text
+//! MINION 3
+//!
+//! **VARIABLES**
+//! BOUND x {1..3}
+//! BOUND y {1..3}
+//!
+//! **CONSTRAINTS**
+//! diseq(x,y)
+//!
+//! **EOF**
+//! ```
+//!
+//! This has 6 solutions, but only 2 should be found.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use minion_rs::ast::{Constant, Constraint, Model, Var, VarDomain, VarName};
+use minion_rs::error::MinionError;
+use minion_rs::{run_minion_with_options, SearchOptions};
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_search_options_sollimit() -> Result<(), MinionError> {
+    let mut model = Model::new();
+    model
+        .named_variables
+        .add_var(String::from("x"), VarDomain::Bound(1, 3));
+    model
+        .named_variables
+        .add_var(String::from("y"), VarDomain::Bound(1, 3));
+
+    model.constraints.push(Constraint::DisEq(
+        Var::NameRef(String::from("x")),
+        Var::NameRef(String::from("y")),
+    ));
+
+    let mut options = SearchOptions::default();
+    options.solution_limit = Some(2);
+    run_minion_with_options(model, &options, callback)?;
+
+    let guard = SOLS_COUNTER.lock().unwrap();
+    assert_eq!(*guard, 2);
+    Ok(())
+}
+
+static SOLS_COUNTER: Mutex<i32> = Mutex::new(0);
+fn callback(_: HashMap<VarName, Constant>) -> bool {
+    #[allow(clippy::unwrap_used)]
+    let mut guard = SOLS_COUNTER.lock().unwrap();
+    *guard += 1;
+    true
+}
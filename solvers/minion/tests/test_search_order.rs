@@ -0,0 +1,94 @@
+//! Solves the following model, where `z` is an auxiliary variable:
+//!
+//! ```text
+//! #TEST SOLCOUNT 4
+//! MINION 3
+//!
+//! **VARIABLES**
+//! BOUND x {1..2}
+//! BOUND y {1..2}
+//! BOUND z {0..1}
+//!
+//! **SEARCH**
+//! PRINT [[x]]
+//! VARORDER STATIC [x,y]
+//! VARORDER AUX [z]
+//!
+//! **CONSTRAINTS**
+//!
+//! **EOF**
+//! ```
+//!
+//! Only one assignment to `z` should be found for each assignment to `x` and `y`, and only `x`
+//! should be returned.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use minion_rs::ast::{Constant, Model, SearchOrder, ValueOrder, VarDomain, VarName};
+use minion_rs::error::MinionError;
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_search_order() -> Result<(), MinionError> {
+    let mut model = Model::new();
+    model
+        .named_variables
+        .add_var(String::from("x"), VarDomain::Bound(1, 2));
+    model
+        .named_variables
+        .add_var(String::from("y"), VarDomain::Bound(1, 2));
+    model
+        .named_variables
+        .add_var(String::from("z"), VarDomain::Bound(0, 1));
+
+    model
+        .search_orders
+        .push(SearchOrder::new(vec![String::from("x"), String::from("y")]));
+    model.print_vars = Some(vec![String::from("x")]);
+
+    minion_rs::run_minion(model, callback)?;
+
+    let guard = SOLUTIONS.lock().unwrap();
+    assert_eq!(guard.len(), 4);
+    for solution in guard.iter() {
+        assert_eq!(solution.keys().collect::<Vec<_>>(), vec!["x"]);
+    }
+    Ok(())
+}
+
+static SOLUTIONS: Mutex<Vec<HashMap<VarName, Constant>>> = Mutex::new(vec![]);
+fn callback(solution: HashMap<VarName, Constant>) -> bool {
+    #[allow(clippy::unwrap_used)]
+    let mut guard = SOLUTIONS.lock().unwrap();
+    guard.push(solution);
+    true
+}
+
+/// Finds the values of `x` in `1..3`, in the order that Minion finds them.
+fn values_in_order(value_order: ValueOrder) -> Result<Vec<Constant>, MinionError> {
+    let mut model = Model::new();
+    model
+        .named_variables
+        .add_var(String::from("x"), VarDomain::Bound(1, 3));
+    model.search_orders.push(SearchOrder {
+        value_order,
+        ..SearchOrder::new(vec![String::from("x")])
+    });
+
+    let mut values = vec![];
+    minion_rs::run_minion(model, |mut solution| {
+        values.extend(solution.remove("x"));
+        true
+    })?;
+    Ok(values)
+}
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_value_order() -> Result<(), MinionError> {
+    let ints = |xs: &[i32]| xs.iter().map(|x| Constant::Integer(*x)).collect::<Vec<_>>();
+
+    assert_eq!(values_in_order(ValueOrder::Ascending)?, ints(&[1, 2, 3]));
+    assert_eq!(values_in_order(ValueOrder::Descending)?, ints(&[3, 2, 1]));
+    Ok(())
+}
@@ -0,0 +1,89 @@
+//! Runs the following model several times at once in a worker pool:
+//!
+//! ```text
+//! MINION 3
+//!
+//! **VARIABLES**
+//! BOUND x {1..3}
+//! BOUND y {1..3}
+//!
+//! **CONSTRAINTS**
+//! diseq(x,y)
+//!
+//! **EOF**
+//! ```
+//!
+//! This has 6 solutions.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::thread;
+
+use minion_rs::ast::{Constant, Constraint, Model, Var, VarDomain, VarName};
+use minion_rs::error::MinionError;
+use minion_rs::worker::WorkerPool;
+use minion_rs::SearchOptions;
+
+#[test]
+#[allow(clippy::panic_in_result_fn)]
+fn test_worker_pool() -> Result<(), MinionError> {
+    let pool = Arc::new(WorkerPool::new(env!("CARGO_BIN_EXE_minion_worker"), 2));
+
+    let handles: Vec<_> = (0..4)
+        .map(|_| {
+            let pool = pool.clone();
+            thread::spawn(move || {
+                let mut solutions: Vec<HashMap<VarName, Constant>> = vec![];
+                let table = pool.run(&model(), &SearchOptions::default(), |solution| {
+                    solutions.push(solution);
+                    true
+                })?;
+                Ok::<_, MinionError>((solutions, table))
+            })
+        })
+        .collect();
+
+    for handle in handles {
+        #[allow(clippy::unwrap_used)]
+        let (solutions, table) = handle.join().unwrap()?;
+        assert_eq!(solutions.len(), 6);
+        assert!(solutions.iter().all(|x| x["x"] != x["y"]));
+        assert!(table.contains_key("Nodes"));
+    }
+
+    // stopping search from the callback
+    let mut n_solutions = 0;
+    pool.run(&model(), &SearchOptions::default(), |_| {
+        n_solutions += 1;
+        n_solutions < 2
+    })?;
+    assert_eq!(n_solutions, 2);
+
+    Ok(())
+}
+
+#[test]
+fn test_missing_worker() {
+    // this is the only test in this binary that uses find_worker.
+    std::env::set_var("MINION_RS_WORKER", "does/not/exist/minion_worker");
+
+    let pool = WorkerPool::with_default_worker(1);
+    assert!(pool.is_err_and(|e| e.to_string().contains("does/not/exist/minion_worker")));
+}
+
+fn model() -> Model {
+    let mut model = Model::new();
+    model
+        .named_variables
+        .add_var(String::from("x"), VarDomain::Bound(1, 3));
+    model
+        .named_variables
+        .add_var(String::from("y"), VarDomain::Bound(1, 3));
+
+    model.constraints.push(Constraint::DisEq(
+        Var::NameRef(String::from("x")),
+        Var::NameRef(String::from("y")),
+    ));
+
+    model
+}
@@ -0,0 +1,128 @@
+#include "libwrapper_ext.h"
+
+#include <iostream>
+#include <sstream>
+#include <string>
+
+namespace {
+// The diagnostics of the last failed call, for getLastError.
+std::string lastError;
+
+// The callback and user data given to the running call of runMinionKeepingErrors.
+bool (*currentCallback)(void*) = nullptr;
+void* currentUserData = nullptr;
+
+// Minion's callback for runMinionKeepingErrors, which passes the user data on to its callback.
+bool callCurrentCallback() {
+  return currentCallback(currentUserData);
+}
+
+// Runs f, which returns one of libwrapper's ReturnCodes, keeping its diagnostics for
+// getLastError if it fails.
+//
+// Only Minion's own output is captured: std::cerr is redirected, rather than the process's
+// standard error, so output from other threads is not affected.
+template <typename F>
+ReturnCodes keepingErrors(F f) {
+  std::ostringstream diagnostics;
+  std::streambuf* cerrBuf = std::cerr.rdbuf(diagnostics.rdbuf());
+
+  ReturnCodes code;
+  try {
+    code = f();
+  } catch (parse_exception& e) {
+    diagnostics << e.what();
+    code = ReturnCodes::INVALID_INSTANCE;
+  } catch (std::exception& e) {
+    diagnostics << e.what();
+    code = ReturnCodes::UNKNOWN_ERROR;
+  } catch (...) {
+    code = ReturnCodes::UNKNOWN_ERROR;
+  }
+
+  std::cerr.rdbuf(cerrBuf);
+
+  if (code == ReturnCodes::OK) {
+    lastError.clear();
+    std::cerr << diagnostics.str();
+  } else {
+    lastError = diagnostics.str();
+  }
+
+  return code;
+}
+} // namespace
+
+ReturnCodes runMinionKeepingErrors(SearchOptions& options, SearchMethod& args,
+                                   ProbSpec::CSPInstance& instance, bool (*callback)(void*),
+                                   void* userData) {
+  currentCallback = callback;
+  currentUserData = userData;
+
+  ReturnCodes code =
+      keepingErrors([&]() { return runMinion(options, args, instance, callCurrentCallback); });
+
+  currentCallback = nullptr;
+  currentUserData = nullptr;
+  return code;
+}
+
+const char* getLastError() {
+  return lastError.c_str();
+}
+
+void searchOrder_setValueOrder(ProbSpec::SearchOrder* searchOrder, ValOrderEnum valueOrder) {
+  searchOrder->val_order.assign(searchOrder->var_order.size(), ValOrder(valueOrder));
+}
+
+ReturnCodes instance_propagateRoot(SearchOptions& options, SearchMethod& args,
+                                   ProbSpec::CSPInstance& instance, std::vector<Var>& vars,
+                                   std::vector<std::vector<int>>& domains, bool& failed) {
+  return keepingErrors([&]() {
+    // set up Minion as runMinion does, but stop before search.
+    resetMinion();
+    getOptions() = options;
+    getState().setTupleListContainer(instance.tupleListContainer);
+    BuildCSP(instance);
+
+    std::vector<AnyVarRef> refs;
+    for (const Var& var : vars) {
+      refs.push_back(get_AnyVarRef_from_Var(var));
+    }
+
+    PropogateCSP(args.preprocess, refs);
+
+    failed = getState().isFailed();
+    if (failed) {
+      return ReturnCodes::OK;
+    }
+
+    for (AnyVarRef& ref : refs) {
+      std::vector<int> domain;
+      for (DomainInt i = ref.min(); i <= ref.max(); i++) {
+        if (ref.inDomain(i)) {
+          domain.push_back(checked_cast<int>(i));
+        }
+      }
+      domains.push_back(domain);
+    }
+
+    return ReturnCodes::OK;
+  });
+}
+
+size_t vec_vec_int_size(std::vector<std::vector<int>>* vec) {
+  return vec->size();
+}
+
+std::vector<int>* vec_vec_int_at(std::vector<std::vector<int>>* vec, size_t i) {
+  return &vec->at(i);
+}
+
+size_t vec_int_size(std::vector<int>* vec) {
+  return vec->size();
+}
+
+int vec_int_at(std::vector<int>* vec, size_t i) {
+  return vec->at(i);
+}
@@ -0,0 +1,45 @@
+// Additions to libminion's C++ API that are used by minion_rs, but are not yet in libwrapper.
+//
+// These are built by build.sh against the vendored Minion, and bound by bindgen with the rest of
+// libwrapper.
+
+#ifndef MINION_RS_LIBWRAPPER_EXT_H
+#define MINION_RS_LIBWRAPPER_EXT_H
+
+#include "libwrapper.h"
+
+// Runs Minion, as runMinion does, calling callback with userData for each solution found.
+//
+// As Minion's own callback takes no arguments, callback and userData are kept in globals for the
+// duration of the run; like runMinion, this must not be called again until it returns.
+//
+// If this fails, anything Minion wrote to std::cerr while running, and the message of the
+// exception it failed with, can be got with getLastError. Otherwise, they are written to std::cerr
+// afterwards.
+ReturnCodes runMinionKeepingErrors(SearchOptions& options, SearchMethod& args,
+                                   ProbSpec::CSPInstance& instance, bool (*callback)(void*),
+                                   void* userData);
+
+// The diagnostics of the last call to runMinionKeepingErrors or instance_propagateRoot, if it
+// failed, or an empty string otherwise. The string is owned by libminion_ext, and is valid until
+// the next of these calls.
+const char* getLastError();
+
+// Sets the order in which values are tried for every variable in the search order.
+void searchOrder_setValueOrder(ProbSpec::SearchOrder* searchOrder, ValOrderEnum valueOrder);
+
+// Builds the given instance, and runs propagation at the root node (at the preprocessing level in
+// args) without any search.
+//
+// If propagation fails, failed is set to true. Otherwise, the values left in the domain of each of
+// vars are added to domains, in the same order. Errors are kept as in runMinionKeepingErrors.
+ReturnCodes instance_propagateRoot(SearchOptions& options, SearchMethod& args,
+                                   ProbSpec::CSPInstance& instance, std::vector<Var>& vars,
+                                   std::vector<std::vector<int>>& domains, bool& failed);
+
+size_t vec_vec_int_size(std::vector<std::vector<int>>* vec);
+std::vector<int>* vec_vec_int_at(std::vector<std::vector<int>>* vec, size_t i);
+size_t vec_int_size(std::vector<int>* vec);
+int vec_int_at(std::vector<int>* vec, size_t i);
+
+#endif
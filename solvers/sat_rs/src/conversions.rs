@@ -41,10 +41,15 @@ pub fn mk_lit(
     }
 }
 
-pub fn conv_to_formula(vec_cnf: &Vec<Vec<i32>>, instance_in_use: &mut SatInstance) -> Result<()> {
+/// Adds the given clauses to the instance, returning the rustsat variable used for each variable
+/// index.
+pub fn conv_to_formula(
+    vec_cnf: &Vec<Vec<i32>>,
+    instance_in_use: &mut SatInstance,
+) -> Result<HashMap<i32, Var>> {
     let mut var_map: HashMap<i32, Var> = HashMap::new();
     for clause in vec_cnf {
         conv_to_clause(clause, instance_in_use, &mut var_map)?;
     }
-    Ok(())
+    Ok(var_map)
 }
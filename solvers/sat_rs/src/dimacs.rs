@@ -0,0 +1,193 @@
+//! Reading and writing CNFs in the DIMACS format.
+//!
+//! Variables can be given names using comment lines of the form `c var <number> <name>`, which
+//! are written before the problem line. Names may contain spaces.
+
+use std::collections::{BTreeMap, HashMap};
+use std::io::{BufRead, Write};
+
+use anyhow::{anyhow, bail, Result};
+use rustsat::instances::SatInstance;
+use rustsat::types::Var;
+
+use crate::conversions::conv_to_formula;
+
+/// A CNF in the DIMACS format.
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Dimacs {
+    /// The number of variables, as given in the problem line.
+    pub n_vars: u32,
+
+    /// The clauses, with each variable given by its number, negated for negative literals.
+    pub clauses: Vec<Vec<i32>>,
+
+    /// The names of variables, as given in comments.
+    pub var_names: BTreeMap<i32, String>,
+}
+
+impl Dimacs {
+    /// Adds the clauses to a new [SatInstance], returning the rustsat variable used for each
+    /// variable number.
+    pub fn to_instance(&self) -> Result<(SatInstance, HashMap<i32, Var>)> {
+        let mut inst = SatInstance::new();
+        let var_map = conv_to_formula(&self.clauses, &mut inst)?;
+        Ok((inst, var_map))
+    }
+}
+
+/// Writes a CNF in the DIMACS format, with a comment naming each variable in `var_names`.
+pub fn write_dimacs(writer: &mut impl Write, dimacs: &Dimacs) -> std::io::Result<()> {
+    for (var, name) in &dimacs.var_names {
+        writeln!(writer, "c var {} {}", var, name)?;
+    }
+
+    writeln!(writer, "p cnf {} {}", dimacs.n_vars, dimacs.clauses.len())?;
+    for clause in &dimacs.clauses {
+        for lit in clause {
+            write!(writer, "{} ", lit)?;
+        }
+        writeln!(writer, "0")?;
+    }
+    Ok(())
+}
+
+/// Reads a CNF in the DIMACS format.
+///
+/// Clauses may span multiple lines. Variables not mentioned in the problem line are an error.
+pub fn parse_dimacs(reader: impl BufRead) -> Result<Dimacs> {
+    let mut dimacs = Dimacs::default();
+    let mut n_clauses: Option<usize> = None;
+    let mut clause: Vec<i32> = Vec::new();
+
+    for (i, line) in reader.lines().enumerate() {
+        let line = line?;
+        let line = line.trim();
+        let line_no = i + 1;
+
+        if let Some(comment) = line.strip_prefix('c') {
+            // c var <number> <name>, where the name is the rest of the line.
+            let named_var = comment
+                .trim_start()
+                .strip_prefix("var ")
+                .and_then(|x| x.trim_start().split_once(' '));
+            if let Some((var, name)) = named_var {
+                let var = var
+                    .parse::<i32>()
+                    .map_err(|_| anyhow!("line {}: invalid variable `{}`", line_no, var))?;
+                dimacs.var_names.insert(var, name.trim().to_owned());
+            }
+            continue;
+        }
+
+        if let Some(problem) = line.strip_prefix('p') {
+            if n_clauses.is_some() {
+                bail!("line {}: duplicate problem line", line_no);
+            }
+            let words: Vec<&str> = problem.split_whitespace().collect();
+            let (n_vars, n) = match words.as_slice() {
+                ["cnf", n_vars, n] => (n_vars.parse::<u32>().ok(), n.parse::<usize>().ok()),
+                _ => (None, None),
+            };
+            let (Some(n_vars), Some(n)) = (n_vars, n) else {
+                bail!("line {}: expected `p cnf <vars> <clauses>`", line_no);
+            };
+            dimacs.n_vars = n_vars;
+            n_clauses = Some(n);
+            continue;
+        }
+
+        // some benchmark files end with a line containing `%`.
+        if line == "%" {
+            break;
+        }
+
+        for word in line.split_whitespace() {
+            if n_clauses.is_none() {
+                bail!("line {}: clause before the problem line", line_no);
+            }
+            let lit = word
+                .parse::<i32>()
+                .map_err(|_| anyhow!("line {}: invalid literal `{}`", line_no, word))?;
+
+            if lit == 0 {
+                dimacs.clauses.push(std::mem::take(&mut clause));
+            } else if lit.unsigned_abs() > dimacs.n_vars {
+                bail!(
+                    "line {}: variable {} is out of range, as there are {} variables",
+                    line_no,
+                    lit.abs(),
+                    dimacs.n_vars
+                );
+            } else {
+                clause.push(lit);
+            }
+        }
+    }
+
+    // the final 0 is optional.
+    if !clause.is_empty() {
+        dimacs.clauses.push(clause);
+    }
+
+    match n_clauses {
+        None => bail!("missing problem line"),
+        Some(n) if n != dimacs.clauses.len() => {
+            bail!("expected {} clauses, but found {}", n, dimacs.clauses.len())
+        }
+        Some(_) => Ok(dimacs),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_dimacs_round_trip() {
+        let dimacs = Dimacs {
+            n_vars: 3,
+            clauses: vec![vec![1, -2], vec![2, 3, -1], vec![]],
+            var_names: BTreeMap::from([(1, "a".to_owned()), (3, "c d".to_owned())]),
+        };
+
+        let mut out: Vec<u8> = Vec::new();
+        write_dimacs(&mut out, &dimacs).unwrap();
+        assert_eq!(
+            String::from_utf8(out.clone()).unwrap(),
+            "c var 1 a\nc var 3 c d\np cnf 3 3\n1 -2 0\n2 3 -1 0\n0\n"
+        );
+
+        assert_eq!(parse_dimacs(out.as_slice()).unwrap(), dimacs);
+    }
+
+    #[test]
+    fn test_parse_dimacs_multiline_clauses() {
+        let input = "c an example\np cnf 2 2\n1\n-2 0 2\n0\n%\n";
+        let dimacs = parse_dimacs(input.as_bytes()).unwrap();
+        assert_eq!(dimacs.clauses, vec![vec![1, -2], vec![2]]);
+        assert!(dimacs.var_names.is_empty());
+    }
+
+    #[test]
+    fn test_parse_dimacs_errors() {
+        // missing problem line
+        assert!(parse_dimacs("1 2 0\n".as_bytes()).is_err());
+
+        // variable out of range
+        assert!(parse_dimacs("p cnf 1 1\n1 2 0\n".as_bytes()).is_err());
+
+        // wrong number of clauses
+        assert!(parse_dimacs("p cnf 2 2\n1 2 0\n".as_bytes()).is_err());
+
+        // invalid literal
+        assert!(parse_dimacs("p cnf 2 1\n1 x 0\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_dimacs_to_instance() {
+        let dimacs = parse_dimacs("p cnf 2 2\n1 -2 0\n2 0\n".as_bytes()).unwrap();
+        let (inst, var_map) = dimacs.to_instance().unwrap();
+        assert_eq!(inst.n_clauses(), 2);
+        assert_eq!(var_map.len(), 2);
+    }
+}
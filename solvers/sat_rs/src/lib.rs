@@ -1,3 +1,5 @@
 pub mod conversions;
+pub mod dimacs;
+pub mod proof;
 pub mod solvers;
 pub mod utils;
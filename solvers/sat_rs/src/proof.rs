@@ -0,0 +1,243 @@
+//! Checking proofs of unsatisfiability written by a SAT solver.
+//!
+//! These checkers are simple, and are meant for testing on small instances rather than for
+//! checking the proofs of hard problems.
+
+use std::collections::{HashMap, HashSet};
+use std::io::BufRead;
+
+use anyhow::{anyhow, bail, Result};
+
+use crate::dimacs::Dimacs;
+
+/// Checks a DRAT proof, in the text format, that the given CNF is unsatisfiable.
+///
+/// Each clause added by the proof must be a reverse unit propagation (RUP) consequence of the
+/// clauses so far, or a resolution asymmetric tautology (RAT) on its first literal. The proof must
+/// add the empty clause.
+pub fn check_drat(dimacs: &Dimacs, proof: impl BufRead) -> Result<()> {
+    let mut clauses: Vec<Vec<i32>> = dimacs.clauses.clone();
+    if clauses.iter().any(|x| x.is_empty()) {
+        return Ok(());
+    }
+
+    for (i, line) in proof.lines().enumerate() {
+        let line = line?;
+        let line_no = i + 1;
+        let (deletion, lits) = match line.trim().strip_prefix('d') {
+            Some(rest) => (true, rest),
+            None => (false, line.trim()),
+        };
+        if lits.is_empty() || lits.starts_with('c') {
+            continue;
+        }
+        let clause = parse_clause(lits).map_err(|e| anyhow!("line {}: {}", line_no, e))?;
+
+        if deletion {
+            // unit clauses are not deleted, as is usual for DRAT checkers.
+            if clause.len() > 1 {
+                let deleted = sorted(&clause);
+                if let Some(j) = clauses.iter().position(|x| sorted(x) == deleted) {
+                    clauses.swap_remove(j);
+                }
+            }
+            continue;
+        }
+
+        if !is_rup(&clauses, &clause) && !is_rat(&clauses, &clause) {
+            bail!("line {}: clause {:?} is not implied", line_no, clause);
+        }
+        if clause.is_empty() {
+            return Ok(());
+        }
+        clauses.push(clause);
+    }
+
+    bail!("the proof does not derive the empty clause")
+}
+
+/// Checks an LRAT proof, in the text format, that the given CNF is unsatisfiable.
+///
+/// The clauses of the CNF are numbered from 1, in order. Each clause added by the proof must
+/// follow by unit propagation on the clauses given as its hints. RAT hints, which are negative,
+/// are not supported. The proof must add the empty clause.
+pub fn check_lrat(dimacs: &Dimacs, proof: impl BufRead) -> Result<()> {
+    let mut clauses: HashMap<i64, Vec<i32>> = (1..).zip(dimacs.clauses.iter().cloned()).collect();
+    if clauses.values().any(|x| x.is_empty()) {
+        return Ok(());
+    }
+
+    for (i, line) in proof.lines().enumerate() {
+        let line = line?;
+        let line_no = i + 1;
+        let mut words = line.split_whitespace();
+        let Some(id) = words.next() else {
+            continue;
+        };
+        if id.starts_with('c') {
+            continue;
+        }
+        let id: i64 = id
+            .parse()
+            .map_err(|_| anyhow!("line {}: invalid clause id `{}`", line_no, id))?;
+        let rest: Vec<&str> = words.collect();
+
+        if rest.first() == Some(&"d") {
+            for word in &rest[1..] {
+                let deleted: i64 = word
+                    .parse()
+                    .map_err(|_| anyhow!("line {}: invalid clause id `{}`", line_no, word))?;
+                clauses.remove(&deleted);
+            }
+            continue;
+        }
+
+        let split = rest
+            .iter()
+            .position(|x| *x == "0")
+            .ok_or(anyhow!("line {}: missing 0 after the clause", line_no))?;
+        let clause = parse_clause(&rest[..=split].join(" "))
+            .map_err(|e| anyhow!("line {}: {}", line_no, e))?;
+        let hints = rest[split + 1..]
+            .iter()
+            .map(|x| x.parse::<i64>())
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| anyhow!("line {}: invalid hints", line_no))?;
+
+        let mut assignment: HashSet<i32> = clause.iter().map(|x| -x).collect();
+        let mut refuted = false;
+        for &hint in hints.iter().take_while(|&&x| x != 0) {
+            if hint < 0 {
+                bail!("line {}: RAT hints are not supported", line_no);
+            }
+            let Some(hint_clause) = clauses.get(&hint) else {
+                bail!("line {}: unknown clause {}", line_no, hint);
+            };
+            let unassigned: Vec<i32> = hint_clause
+                .iter()
+                .copied()
+                .filter(|x| !assignment.contains(&-x))
+                .collect();
+            match unassigned.as_slice() {
+                [] => {
+                    refuted = true;
+                    break;
+                }
+                [lit] => {
+                    assignment.insert(*lit);
+                }
+                _ => bail!("line {}: hint {} is not unit", line_no, hint),
+            }
+        }
+        if !refuted {
+            bail!("line {}: clause {:?} is not implied", line_no, clause);
+        }
+        if clause.is_empty() {
+            return Ok(());
+        }
+        clauses.insert(id, clause);
+    }
+
+    bail!("the proof does not derive the empty clause")
+}
+
+/// Parses the literals of a clause, ending with 0.
+fn parse_clause(line: &str) -> Result<Vec<i32>> {
+    let mut clause = Vec::new();
+    for word in line.split_whitespace() {
+        let lit: i32 = word
+            .parse()
+            .map_err(|_| anyhow!("invalid literal `{}`", word))?;
+        if lit == 0 {
+            return Ok(clause);
+        }
+        clause.push(lit);
+    }
+    bail!("missing 0 at the end of the clause")
+}
+
+fn sorted(clause: &[i32]) -> Vec<i32> {
+    let mut clause = clause.to_vec();
+    clause.sort();
+    clause
+}
+
+/// Whether unit propagation on the clauses, after assigning the negation of the clause, gives a
+/// conflict.
+fn is_rup(clauses: &[Vec<i32>], clause: &[i32]) -> bool {
+    let mut assignment: HashSet<i32> = clause.iter().map(|x| -x).collect();
+    loop {
+        let mut changed = false;
+        for c in clauses {
+            if c.iter().any(|x| assignment.contains(x)) {
+                continue;
+            }
+            let unassigned: Vec<i32> = c
+                .iter()
+                .copied()
+                .filter(|x| !assignment.contains(&-x))
+                .collect();
+            match unassigned.as_slice() {
+                [] => return true,
+                [lit] => {
+                    assignment.insert(*lit);
+                    changed = true;
+                }
+                _ => {}
+            }
+        }
+        if !changed {
+            return false;
+        }
+    }
+}
+
+/// Whether the clause is a RAT on its first literal: every resolvent with a clause containing the
+/// negation of that literal is a RUP.
+fn is_rat(clauses: &[Vec<i32>], clause: &[i32]) -> bool {
+    let Some(&pivot) = clause.first() else {
+        return false;
+    };
+    clauses.iter().filter(|x| x.contains(&-pivot)).all(|other| {
+        let resolvent: Vec<i32> = clause
+            .iter()
+            .copied()
+            .chain(other.iter().copied().filter(|&x| x != -pivot))
+            .collect();
+        is_rup(clauses, &resolvent)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dimacs::parse_dimacs;
+
+    // (1 OR 2) AND (-1 OR 2) AND (1 OR -2) AND (-1 OR -2)
+    const UNSAT: &str = "p cnf 2 4\n1 2 0\n-1 2 0\n1 -2 0\n-1 -2 0\n";
+
+    #[test]
+    fn test_check_drat() {
+        let dimacs = parse_dimacs(UNSAT.as_bytes()).unwrap();
+        assert!(check_drat(&dimacs, "2 0\nd 1 2 0\n0\n".as_bytes()).is_ok());
+        assert!(check_drat(&dimacs, "-1 -2 0\n1 0\n0\n".as_bytes()).is_ok());
+
+        // without (-1 OR -2), -2 is neither a RUP nor a RAT.
+        assert!(check_drat(&dimacs, "d -1 -2 0\n-2 0\n0\n".as_bytes()).is_err());
+
+        // the empty clause is never derived.
+        assert!(check_drat(&dimacs, "2 0\n".as_bytes()).is_err());
+    }
+
+    #[test]
+    fn test_check_lrat() {
+        let dimacs = parse_dimacs(UNSAT.as_bytes()).unwrap();
+        assert!(check_lrat(&dimacs, "5 2 0 1 2 0\n6 0 5 3 4 0\n".as_bytes()).is_ok());
+
+        // clause 1 alone does not give 2.
+        assert!(check_lrat(&dimacs, "5 2 0 1 0\n6 0 5 3 4 0\n".as_bytes()).is_err());
+
+        // clause 5 was deleted.
+        assert!(check_lrat(&dimacs, "5 2 0 1 2 0\n5 d 5 0\n6 0 5 3 4 0\n".as_bytes()).is_err());
+    }
+}
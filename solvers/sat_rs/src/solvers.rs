@@ -1,10 +1,296 @@
-use anyhow::{Error, Result};
-use rustsat::instances::SatInstance;
-use rustsat::solvers::{Solve, SolverResult};
-use rustsat_minisat::simp::Minisat;
+use std::fmt::Display;
+use std::path::Path;
+use std::str::FromStr;
 
+use anyhow::{anyhow, bail, Result};
+use rustsat::instances::{Cnf, SatInstance};
+use rustsat::solvers::{
+    Interrupt, InterruptSolver, Solve, SolveIncremental, SolveStats, SolverResult, SolverStats,
+};
+use rustsat::types::{Clause, Lit, TernaryVal};
+
+/// A SAT solver backend.
+///
+/// This is implemented for each of the rustsat solvers enabled by cargo features, and for boxed
+/// solvers, so that the backend can be chosen at runtime using [SatBackend].
 pub trait Solver {
-    fn solve(&self, instance: &SatInstance) -> Result<SolverResult>;
+    /// Adds the clauses of the given CNF to the solver.
+    fn add_cnf(&mut self, cnf: Cnf) -> Result<()>;
+
+    /// Adds a clause to the solver.
+    fn add_clause(&mut self, clause: Clause) -> Result<()>;
+
+    /// Solves the clauses added so far.
+    fn solve(&mut self) -> Result<SolverResult>;
+
+    /// Solves the clauses added so far, under the given assumptions.
+    ///
+    /// The assumptions only hold for this call, so the solver can be reused with different
+    /// assumptions without adding or removing clauses.
+    fn solve_assumps(&mut self, assumps: &[Lit]) -> Result<SolverResult>;
+
+    /// Gets the value of a literal in the last solution found.
+    fn lit_val(&self, lit: Lit) -> Result<TernaryVal>;
+
+    /// Gets a function that interrupts the solver, which can be called from another thread.
+    fn interrupter(&mut self) -> Box<dyn FnMut() + Send>;
+
+    /// Gets the statistics kept by the solver.
+    fn stats(&self) -> SolverStats;
+
+    /// Writes a proof of unsatisfiability to the given file, in the given format.
+    ///
+    /// This must be called before any clauses are added. Fails if the solver cannot write proofs.
+    fn trace_proof(&mut self, path: &Path, format: ProofFormat) -> Result<()> {
+        let _ = (path, format);
+        bail!("this SAT backend cannot write proofs")
+    }
+}
+
+/// Implements [Solver] for a rustsat solver, optionally using the given function to write proofs.
+macro_rules! impl_solver {
+    ($solver:ty $(, trace_proof = $trace_proof:path)?) => {
+        impl Solver for $solver {
+            fn add_cnf(&mut self, cnf: Cnf) -> Result<()> {
+                Solve::add_cnf(self, cnf)
+            }
+
+            fn add_clause(&mut self, clause: Clause) -> Result<()> {
+                Solve::add_clause(self, clause)
+            }
+
+            fn solve(&mut self) -> Result<SolverResult> {
+                Solve::solve(self)
+            }
+
+            fn solve_assumps(&mut self, assumps: &[Lit]) -> Result<SolverResult> {
+                SolveIncremental::solve_assumps(self, assumps)
+            }
+
+            fn lit_val(&self, lit: Lit) -> Result<TernaryVal> {
+                Solve::lit_val(self, lit)
+            }
+
+            fn interrupter(&mut self) -> Box<dyn FnMut() + Send> {
+                let mut interrupter = Interrupt::interrupter(self);
+                Box::new(move || interrupter.interrupt())
+            }
+
+            fn stats(&self) -> SolverStats {
+                SolveStats::stats(self)
+            }
+
+            $(
+                fn trace_proof(&mut self, path: &Path, format: ProofFormat) -> Result<()> {
+                    $trace_proof(self, path, format)
+                }
+            )?
+        }
+    };
+}
+
+#[cfg(feature = "minisat")]
+impl_solver!(rustsat_minisat::core::Minisat);
+
+#[cfg(feature = "minisat")]
+impl_solver!(rustsat_minisat::simp::Minisat);
+
+#[cfg(feature = "cadical")]
+impl_solver!(
+    rustsat_cadical::CaDiCaL<'static, 'static>,
+    trace_proof = cadical_trace_proof
+);
+
+#[cfg(feature = "cadical")]
+fn cadical_trace_proof(
+    solver: &mut rustsat_cadical::CaDiCaL<'static, 'static>,
+    path: &Path,
+    format: ProofFormat,
+) -> Result<()> {
+    let format = match format {
+        ProofFormat::Drat => rustsat_cadical::ProofFormat::Drat { binary: false },
+        ProofFormat::Lrat => rustsat_cadical::ProofFormat::Lrat { binary: false },
+    };
+    solver.trace_proof(path, format)?;
+    Ok(())
+}
+
+#[cfg(feature = "glucose")]
+impl_solver!(rustsat_glucose::core::Glucose);
+
+impl Solver for Box<dyn Solver> {
+    fn add_cnf(&mut self, cnf: Cnf) -> Result<()> {
+        (**self).add_cnf(cnf)
+    }
+
+    fn add_clause(&mut self, clause: Clause) -> Result<()> {
+        (**self).add_clause(clause)
+    }
+
+    fn solve(&mut self) -> Result<SolverResult> {
+        (**self).solve()
+    }
+
+    fn solve_assumps(&mut self, assumps: &[Lit]) -> Result<SolverResult> {
+        (**self).solve_assumps(assumps)
+    }
+
+    fn lit_val(&self, lit: Lit) -> Result<TernaryVal> {
+        (**self).lit_val(lit)
+    }
+
+    fn interrupter(&mut self) -> Box<dyn FnMut() + Send> {
+        (**self).interrupter()
+    }
+
+    fn stats(&self) -> SolverStats {
+        (**self).stats()
+    }
+
+    fn trace_proof(&mut self, path: &Path, format: ProofFormat) -> Result<()> {
+        (**self).trace_proof(path, format)
+    }
+}
+
+/// The formats of proofs of unsatisfiability that can be written by [Solver::trace_proof].
+///
+/// Both are written as text, and can be checked using the functions in [crate::proof].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum ProofFormat {
+    /// Deletion resolution asymmetric tautology proofs, giving each derived clause.
+    #[default]
+    Drat,
+
+    /// Linear RAT proofs, which also give the clauses used to derive each clause.
+    Lrat,
+}
+
+impl Display for ProofFormat {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProofFormat::Drat => write!(f, "drat"),
+            ProofFormat::Lrat => write!(f, "lrat"),
+        }
+    }
+}
+
+impl FromStr for ProofFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "drat" => Ok(ProofFormat::Drat),
+            "lrat" => Ok(ProofFormat::Lrat),
+            _ => Err(format!(
+                "unknown proof format `{}`: expected drat or lrat",
+                s
+            )),
+        }
+    }
+}
+
+/// The SAT solvers that can be used as a [Solver].
+///
+/// Each backend is only available if its cargo feature is enabled: `minisat` (enabled by default),
+/// `cadical` or `glucose`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SatBackend {
+    /// Minisat, without preprocessing.
+    #[default]
+    MinisatCore,
+
+    /// Minisat, with its simplifying preprocessor.
+    MinisatSimp,
+
+    /// CaDiCaL.
+    Cadical,
+
+    /// Glucose.
+    Glucose,
+}
+
+impl SatBackend {
+    /// All backends, whether or not they are enabled.
+    pub const ALL: [SatBackend; 4] = [
+        SatBackend::MinisatCore,
+        SatBackend::MinisatSimp,
+        SatBackend::Cadical,
+        SatBackend::Glucose,
+    ];
+
+    /// The cargo feature that enables this backend.
+    pub fn feature(&self) -> &'static str {
+        match self {
+            SatBackend::MinisatCore | SatBackend::MinisatSimp => "minisat",
+            SatBackend::Cadical => "cadical",
+            SatBackend::Glucose => "glucose",
+        }
+    }
+
+    /// Whether this backend was enabled at compile time.
+    pub fn is_enabled(&self) -> bool {
+        match self {
+            SatBackend::MinisatCore | SatBackend::MinisatSimp => cfg!(feature = "minisat"),
+            SatBackend::Cadical => cfg!(feature = "cadical"),
+            SatBackend::Glucose => cfg!(feature = "glucose"),
+        }
+    }
+
+    /// Whether this backend can write proofs of unsatisfiability, using [Solver::trace_proof].
+    pub fn supports_proofs(&self) -> bool {
+        matches!(self, SatBackend::Cadical)
+    }
+
+    /// Creates a new solver of this kind.
+    ///
+    /// Fails if the backend's cargo feature is not enabled.
+    pub fn new_solver(&self) -> Result<Box<dyn Solver>> {
+        match self {
+            #[cfg(feature = "minisat")]
+            SatBackend::MinisatCore => Ok(Box::new(rustsat_minisat::core::Minisat::default())),
+
+            #[cfg(feature = "minisat")]
+            SatBackend::MinisatSimp => Ok(Box::new(rustsat_minisat::simp::Minisat::default())),
+
+            #[cfg(feature = "cadical")]
+            SatBackend::Cadical => Ok(Box::new(rustsat_cadical::CaDiCaL::default())),
+
+            #[cfg(feature = "glucose")]
+            SatBackend::Glucose => Ok(Box::new(rustsat_glucose::core::Glucose::default())),
+
+            #[allow(unreachable_patterns)]
+            _ => Err(anyhow!(
+                "the {} SAT backend is not enabled: build with the `{}` feature",
+                self,
+                self.feature()
+            )),
+        }
+    }
+}
+
+impl Display for SatBackend {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            SatBackend::MinisatCore => write!(f, "minisat-core"),
+            SatBackend::MinisatSimp => write!(f, "minisat-simp"),
+            SatBackend::Cadical => write!(f, "cadical"),
+            SatBackend::Glucose => write!(f, "glucose"),
+        }
+    }
+}
+
+impl FromStr for SatBackend {
+    type Err = String;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        SatBackend::ALL
+            .into_iter()
+            .find(|x| x.to_string().eq_ignore_ascii_case(s))
+            .ok_or(format!(
+                "unknown SAT backend `{}`: expected one of minisat-core, minisat-simp, cadical or glucose",
+                s
+            ))
+    }
 }
 
 pub struct SatSolver<SolverType> {
@@ -16,8 +302,9 @@ impl<SolverType: Solver> SatSolver<SolverType> {
         SatSolver { solver }
     }
 
-    pub fn solve(&self, inst: SatInstance) -> Result<SolverResult> {
-        self.solver.solve(&inst)
+    pub fn solve(&mut self, inst: SatInstance) -> Result<SolverResult> {
+        self.solver.add_cnf(inst.into_cnf().0)?;
+        self.solver.solve()
     }
 
     pub fn solver_instance(&self) -> &SolverType {
@@ -25,13 +312,6 @@ impl<SolverType: Solver> SatSolver<SolverType> {
     }
 }
 
-impl Solver for Minisat {
-    fn solve(&self, instance: &SatInstance) -> Result<SolverResult, Error> {
-        let res: Result<SolverResult, Error> = self.solve(instance);
-        res
-    }
-}
-
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -48,8 +328,8 @@ mod tests {
         crate::conversions::conv_to_clause(&clause1, &mut instance, &mut var_map).unwrap();
         crate::conversions::conv_to_clause(&clause2, &mut instance, &mut var_map).unwrap();
 
-        let solver = Minisat::default();
-        let sat_solver = SatSolver::new(solver);
+        let solver = SatBackend::MinisatSimp.new_solver().unwrap();
+        let mut sat_solver = SatSolver::new(solver);
         let result = sat_solver.solve(instance).unwrap();
 
         assert_eq!(result, SolverResult::Sat);
@@ -65,10 +345,41 @@ mod tests {
         crate::conversions::conv_to_clause(&clause1, &mut instance, &mut var_map).unwrap();
         crate::conversions::conv_to_clause(&clause2, &mut instance, &mut var_map).unwrap();
 
-        let solver = Minisat::default();
-        let sat_solver = SatSolver::new(solver);
+        let solver = SatBackend::MinisatSimp.new_solver().unwrap();
+        let mut sat_solver = SatSolver::new(solver);
         let result = sat_solver.solve(instance).unwrap();
 
         assert_eq!(result, SolverResult::Unsat);
     }
+
+    #[test]
+    fn test_enabled_backends_agree() {
+        // (1 OR 2) AND (-1 OR 2) AND (1 OR -2) is satisfiable, but not with (-1 OR -2).
+        for backend in SatBackend::ALL.into_iter().filter(|x| x.is_enabled()) {
+            for (problem, expected) in [
+                (
+                    vec![vec![1, 2], vec![-1, 2], vec![1, -2]],
+                    SolverResult::Sat,
+                ),
+                (
+                    vec![vec![1, 2], vec![-1, 2], vec![1, -2], vec![-1, -2]],
+                    SolverResult::Unsat,
+                ),
+            ] {
+                let mut instance = SatInstance::new();
+                crate::conversions::conv_to_formula(&problem, &mut instance).unwrap();
+
+                let mut sat_solver = SatSolver::new(backend.new_solver().unwrap());
+                assert_eq!(sat_solver.solve(instance).unwrap(), expected, "{}", backend);
+            }
+        }
+    }
+
+    #[test]
+    fn test_backend_names() {
+        for backend in SatBackend::ALL {
+            assert_eq!(backend.to_string().parse::<SatBackend>(), Ok(backend));
+        }
+        assert!("lingeling".parse::<SatBackend>().is_err());
+    }
 }
@@ -1,20 +1,22 @@
-use crate::solvers::SatSolver;
+use crate::solvers::{SatBackend, SatSolver, Solver};
 use crate::conversions::conv_to_formula;
 use anyhow::{Error, Result};
 use rustsat::instances::SatInstance;
 use rustsat::solvers::SolverResult;
-use rustsat_minisat::simp::Minisat;
 
-pub fn initialize_solver(vec_problem: &Vec<Vec<i32>>) -> Result<(SatSolver<Minisat>, SatInstance)> {
+/// Converts the given CNF into a SAT instance, and creates a solver for it using the default
+/// [SatBackend].
+pub fn initialize_solver(
+    vec_problem: &Vec<Vec<i32>>,
+) -> Result<(SatSolver<Box<dyn Solver>>, SatInstance)> {
     let mut inst: SatInstance = SatInstance::new();
     conv_to_formula(vec_problem, &mut inst)?;
-    let minisat_solver = Minisat::default();
-    let sat_solver = SatSolver::new(minisat_solver);
+    let sat_solver = SatSolver::new(SatBackend::default().new_solver()?);
     Ok((sat_solver, inst))
 }
 
 pub fn solve_problem(
-    sat_solver: &SatSolver<Minisat>,
+    sat_solver: &mut SatSolver<Box<dyn Solver>>,
     instance: SatInstance,
 ) -> Result<SolverResult, Error> {
     let res = sat_solver.solve(instance)?;
@@ -37,8 +39,8 @@ mod tests {
             vec![1, -2],
         ];
 
-        let (sat_solver, inst) = initialize_solver(&problem).unwrap();
-        let result = solve_problem(&sat_solver, inst).unwrap();
+        let (mut sat_solver, inst) = initialize_solver(&problem).unwrap();
+        let result = solve_problem(&mut sat_solver, inst).unwrap();
 
         assert_eq!(result, SolverResult::Sat);
     }
@@ -47,8 +49,8 @@ mod tests {
     fn test_initialize_and_solve_unsatisfiable() {
         let problem = vec![vec![1], vec![-1]];
 
-        let (sat_solver, inst) = initialize_solver(&problem).unwrap();
-        let result = solve_problem(&sat_solver, inst).unwrap();
+        let (mut sat_solver, inst) = initialize_solver(&problem).unwrap();
+        let result = solve_problem(&mut sat_solver, inst).unwrap();
 
         assert_eq!(result, SolverResult::Unsat);
     }